@@ -40,12 +40,26 @@ pub enum Gap {
     Large
 }
 
-#[derive(Copy, Clone)]
+#[derive(
+    PartialEq, Eq, Hash, Serialize, Deserialize, Ord,
+    PartialOrd, Copy, Clone, Debug
+    )
+]
 pub enum DisplaySuit {
     Clubs,
     Diamonds,
-    Spades,
-    Hearts
+    Hearts,
+    Spades
+}
+
+#[derive(
+    PartialEq, Eq, Hash, Serialize, Deserialize, Ord,
+    PartialOrd, Copy, Clone, Debug
+    )
+]
+pub struct SpecificCard {
+    pub rank: Card,
+    pub suit: DisplaySuit,
 }
 
 impl Card {
@@ -341,6 +355,124 @@ impl Distribution<Card> for Standard {
     }
 }
 
+impl DisplaySuit {
+    pub fn iterator() -> Iter<'static, DisplaySuit> {
+        use self::DisplaySuit::*;
+        static SUITS: [DisplaySuit; 4] = [Clubs, Diamonds, Hearts, Spades];
+        SUITS.iter()
+    }
+}
+
+impl fmt::Display for DisplaySuit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            DisplaySuit::Clubs => "c",
+            DisplaySuit::Diamonds => "d",
+            DisplaySuit::Hearts => "h",
+            DisplaySuit::Spades => "s",
+        })
+    }
+}
+
+impl From<DisplaySuit> for u8 {
+    fn from(suit: DisplaySuit) -> u8 {
+        match suit {
+            DisplaySuit::Clubs => 0,
+            DisplaySuit::Diamonds => 1,
+            DisplaySuit::Hearts => 2,
+            DisplaySuit::Spades => 3,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseDisplaySuitError;
+impl FromStr for DisplaySuit {
+    type Err = ParseDisplaySuitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "c" => Ok(DisplaySuit::Clubs),
+            "d" => Ok(DisplaySuit::Diamonds),
+            "h" => Ok(DisplaySuit::Hearts),
+            "s" => Ok(DisplaySuit::Spades),
+            _ => Err(ParseDisplaySuitError)
+        }
+    }
+}
+
+impl Distribution<DisplaySuit> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> DisplaySuit {
+        match rng.gen_range(0,4) {
+            0 => DisplaySuit::Clubs,
+            1 => DisplaySuit::Diamonds,
+            2 => DisplaySuit::Hearts,
+            _ => DisplaySuit::Spades
+        }
+    }
+}
+
+impl SpecificCard {
+    pub fn new(rank: Card, suit: DisplaySuit) -> Self {
+        Self { rank, suit }
+    }
+
+    /// All 52 cards of the deck, lowest first.
+    pub fn all() -> Vec<SpecificCard> {
+        Card::iterator()
+            .flat_map(|rank| DisplaySuit::iterator().map(move |suit| SpecificCard::new(*rank, *suit)))
+            .collect()
+    }
+
+    /// Position of the card in a 52 card deck, `0` for 2c up to `51` for As.
+    pub fn index(&self) -> u8 {
+        (u8::from(self.rank) - 2) * 4 + u8::from(self.suit)
+    }
+
+    pub fn from_index(index: u8) -> Option<SpecificCard> {
+        let rank = Card::iterator().nth((index / 4) as usize)?;
+        let suit = DisplaySuit::iterator().nth((index % 4) as usize)?;
+        Some(SpecificCard::new(*rank, *suit))
+    }
+
+    pub fn svg_bytes(&self) -> &'static [u8] {
+        self.rank.svg_bytes(Some(self.suit))
+    }
+}
+
+impl fmt::Display for SpecificCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseSpecificCardError;
+impl FromStr for SpecificCard {
+    type Err = ParseSpecificCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.chars().count() != 2 {
+            return Err(ParseSpecificCardError);
+        }
+        let rank = s.get(0..1).and_then(|x| x.to_uppercase().parse::<Card>().ok());
+        let suit = s.get(1..2).and_then(|x| x.to_lowercase().parse::<DisplaySuit>().ok());
+        match (rank, suit) {
+            (Some(rank), Some(suit)) => Ok(SpecificCard { rank, suit }),
+            _ => Err(ParseSpecificCardError)
+        }
+    }
+}
+
+impl Distribution<SpecificCard> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SpecificCard {
+        SpecificCard {
+            rank: rng.gen(),
+            suit: rng.gen(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use quickcheck_macros::quickcheck;
@@ -369,8 +501,49 @@ mod tests {
     }
     
 
+    impl Arbitrary for super::DisplaySuit {
+        fn arbitrary<G: Gen>(g: &mut G) -> super::DisplaySuit {
+            let x = g.next_u32() % 4;
+            match x {
+                0 => super::DisplaySuit::Clubs,
+                1 => super::DisplaySuit::Diamonds,
+                2 => super::DisplaySuit::Hearts,
+                3 => super::DisplaySuit::Spades,
+                _ => unreachable!()
+            }
+        }
+    }
+
+    impl Arbitrary for super::SpecificCard {
+        fn arbitrary<G: Gen>(g: &mut G) -> super::SpecificCard {
+            super::SpecificCard {
+                rank: super::Card::arbitrary(g),
+                suit: super::DisplaySuit::arbitrary(g),
+            }
+        }
+    }
+
     #[quickcheck]
     fn parse_display_card(card: super::Card) -> bool {
         format!("{}", card).parse::<super::Card>().unwrap() == card
     }
+
+    #[quickcheck]
+    fn parse_display_specific_card(card: super::SpecificCard) -> bool {
+        format!("{}", card).parse::<super::SpecificCard>().unwrap() == card
+    }
+
+    #[quickcheck]
+    fn specific_card_index_round_trips(card: super::SpecificCard) -> bool {
+        super::SpecificCard::from_index(card.index()) == Some(card)
+    }
+
+    #[test]
+    fn deck_has_52_distinct_cards() {
+        let deck = super::SpecificCard::all();
+        let indices = deck.iter().map(|c| c.index()).collect::<std::collections::HashSet<u8>>();
+        assert_eq!(deck.len(), 52);
+        assert_eq!(indices.len(), 52);
+        assert_eq!("As".parse::<super::SpecificCard>().unwrap().index(), 51);
+    }
 }
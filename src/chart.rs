@@ -0,0 +1,207 @@
+use std::error::Error;
+use std::path::Path;
+
+use crate::fileio;
+use crate::hand::Hand;
+use crate::range::{Range, TOTAL_COMBOS};
+use crate::range_file;
+use crate::review::item::PokerAction;
+use crate::strategy::{self, Strategy};
+
+// Colours match the hand matrix on the ranges screen.
+const RANGE_COLOR: &str = "#88d0f7";
+const RAISE_COLOR: &str = "#f0786e";
+const CALL_COLOR: &str = "#82d28c";
+const FOLD_COLOR: &str = "#ffffff";
+
+const CELL: usize = 48;
+const GAP: usize = 4;
+const MARGIN: usize = 16;
+const TITLE_HEIGHT: usize = 32;
+const LEGEND_ROW: usize = 24;
+
+/// The range as a 13x13 SVG chart laid out like the hand matrix. Mixed
+/// hands are filled from the bottom up to their weight.
+pub fn range_svg(range: &Range) -> String {
+    let title = format!(
+        "{} ({:.0} combos, {:.1}%)",
+        range.label(),
+        range.combo_count(),
+        range.percent()
+    );
+    let mut legend = vec![(RANGE_COLOR, String::from("In range"))];
+    if range.hands.values().any(|weight| *weight < 1.0) {
+        legend.push((RANGE_COLOR, String::from("Partly filled hands are mixed")));
+    }
+    svg(&title, &legend, |hand, x, y| {
+        let weight = range.weight(&hand);
+        let mut cell = rect(x, y, CELL as f32, CELL as f32, FOLD_COLOR, 5);
+        if weight > 0.0 {
+            let height = CELL as f32 * weight.min(1.0);
+            cell.push_str(&rect(x, y + CELL as f32 - height, CELL as f32, height, RANGE_COLOR, 5));
+        }
+        cell
+    })
+}
+
+/// The strategy as a 13x13 SVG chart, each cell split from the left into
+/// one stripe per action as wide as its frequency.
+pub fn strategy_svg(strategy: &Strategy) -> String {
+    let mut actions = strategy
+        .actions
+        .iter()
+        .map(|(action, _)| action.clone())
+        .collect::<Vec<PokerAction>>();
+    actions.push(PokerAction::Fold);
+    let legend = actions
+        .iter()
+        .map(|action| {
+            let combos: f32 = Hand::all()
+                .iter()
+                .map(|hand| strategy.frequency(hand, action) * hand.combo_count() as f32)
+                .sum();
+            (
+                action_color(action),
+                format!("{} {:.1}%", strategy::action_name(action), combos / TOTAL_COMBOS * 100.0),
+            )
+        })
+        .collect::<Vec<(&str, String)>>();
+    svg(&strategy.name, &legend, |hand, x, y| {
+        let mut cell = rect(x, y, CELL as f32, CELL as f32, FOLD_COLOR, 5);
+        let mut stripe_x = x;
+        for (action, frequency) in strategy.distribution(&hand) {
+            if action == PokerAction::Fold {
+                continue;
+            }
+            let width = CELL as f32 * frequency;
+            cell.push_str(&rect(stripe_x, y, width, CELL as f32, action_color(&action), 0));
+            stripe_x += width;
+        }
+        cell.push_str(&format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="5" fill="none" stroke="black"/>"#,
+            x, y, CELL, CELL
+        ));
+        cell
+    })
+}
+
+/// Writes the chart of the saved range, or failing that strategy, called
+/// `name` to `path`. Needs no display, so it works on a headless machine.
+pub fn export_saved_chart(name: &str, path: &Path) -> Result<(), Box<dyn Error>> {
+    let chart = match fileio::load_ranges()?.iter().find(|range| range.name == name) {
+        Some(range) => range_svg(range),
+        None => match fileio::load_strategies()?.iter().find(|strategy| strategy.name == name) {
+            Some(strategy) => strategy_svg(strategy),
+            None => return Err(format!("no saved range or strategy called \"{}\"", name).into()),
+        },
+    };
+    fileio::export_chart(path, &chart)
+}
+
+/// The file a chart called `name` is saved to inside a folder.
+pub fn file_name(name: &str) -> String {
+    format!("{}.svg", range_file::safe_name(name))
+}
+
+fn action_color(action: &PokerAction) -> &'static str {
+    match action {
+        PokerAction::Raise(_) => RAISE_COLOR,
+        PokerAction::Call | PokerAction::Check => CALL_COLOR,
+        PokerAction::Fold => FOLD_COLOR,
+    }
+}
+
+/// Lays out a title, the 13x13 grid of cells drawn by `cell` from their top
+/// left corner, hand labels and a legend below.
+fn svg<F>(title: &str, legend: &[(&str, String)], cell: F) -> String
+where
+    F: Fn(Hand, f32, f32) -> String,
+{
+    let grid = 13 * CELL + 12 * GAP;
+    let width = grid + 2 * MARGIN;
+    let height = MARGIN + TITLE_HEIGHT + grid + MARGIN + legend.len() * LEGEND_ROW + MARGIN;
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif">"#,
+        w = width,
+        h = height
+    );
+    svg.push_str(r#"<rect width="100%" height="100%" fill="white"/>"#);
+    svg.push_str(&format!(
+        r#"<text x="{}" y="{}" font-size="20">{}</text>"#,
+        MARGIN,
+        MARGIN + 20,
+        escape(title)
+    ));
+    for row in 0..13 {
+        for column in 0..13 {
+            let hand = range_file::grid_hand(row, column);
+            let x = (MARGIN + column * (CELL + GAP)) as f32;
+            let y = (MARGIN + TITLE_HEIGHT + row * (CELL + GAP)) as f32;
+            svg.push_str(&cell(hand, x, y));
+            svg.push_str(&format!(
+                r#"<text x="{}" y="{}" font-size="16" text-anchor="middle" dominant-baseline="central" fill-opacity="0.7">{}</text>"#,
+                x + CELL as f32 / 2.0,
+                y + CELL as f32 / 2.0,
+                hand
+            ));
+        }
+    }
+    let legend_top = MARGIN + TITLE_HEIGHT + grid + MARGIN;
+    for (i, (color, label)) in legend.iter().enumerate() {
+        let y = (legend_top + i * LEGEND_ROW) as f32;
+        svg.push_str(&rect(MARGIN as f32, y, 16.0, 16.0, color, 3));
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}" font-size="14" dominant-baseline="central">{}</text>"#,
+            MARGIN + 24,
+            y + 8.0,
+            escape(label)
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn rect(x: f32, y: f32, width: f32, height: f32, fill: &str, radius: u8) -> String {
+    format!(
+        r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="{}" stroke="black"/>"#,
+        x, y, width, height, radius, fill
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hand::Hand;
+    use crate::range::Range;
+    use crate::review::item::PokerAction;
+    use crate::strategy::Strategy;
+
+    #[test]
+    fn range_charts_label_every_hand() {
+        let mut range = Range::new();
+        range.name = String::from("BTN <open>");
+        range.set_weight("AKo".parse::<Hand>().unwrap(), 0.5);
+        let svg = super::range_svg(&range);
+        for hand in Hand::all() {
+            assert!(svg.contains(&format!(">{}</text>", hand)));
+        }
+        assert!(svg.contains("BTN &lt;open&gt; (6 combos, 0.5%)"));
+        assert!(svg.contains(r##"height="24" rx="5" fill="#88d0f7""##));
+        assert!(svg.contains("Partly filled hands are mixed"));
+    }
+
+    #[test]
+    fn strategy_charts_list_action_frequencies() {
+        let mut strategy = Strategy::new();
+        strategy.set_frequency("AA".parse::<Hand>().unwrap(), PokerAction::Raise(String::from("2.5")), 1.0);
+        strategy.set_frequency("KK".parse::<Hand>().unwrap(), PokerAction::Call, 0.5);
+        let svg = super::strategy_svg(&strategy);
+        assert!(svg.contains(">Raise 2.5 BB 0.5%</text>"));
+        assert!(svg.contains(">Call 0.2%</text>"));
+        assert!(svg.contains(">Fold 99.3%</text>"));
+        assert!(svg.contains(r##"width="24" height="48" rx="0" fill="#82d28c""##));
+    }
+}
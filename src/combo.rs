@@ -0,0 +1,178 @@
+use std::str::FromStr;
+use std::fmt;
+use serde::{Serialize, Deserialize};
+
+use crate::card::{Card, DisplaySuit, SpecificCard};
+use crate::hand::{Hand, Suit};
+
+/// Two exact hole cards, e.g. `AsKh`. The higher card is always `first`.
+#[derive(
+    PartialEq, Eq, Hash, Serialize, Deserialize, Ord,
+    PartialOrd, Copy, Clone, Debug
+    )
+]
+pub struct Combo {
+    pub first: SpecificCard,
+    pub second: SpecificCard,
+}
+
+impl Combo {
+    pub fn new(a: SpecificCard, b: SpecificCard) -> Option<Combo> {
+        if a == b {
+            None
+        } else {
+            Some(Combo {
+                first: SpecificCard::max(a, b),
+                second: SpecificCard::min(a, b),
+            })
+        }
+    }
+
+    pub fn cards(&self) -> [SpecificCard; 2] {
+        [self.first, self.second]
+    }
+
+    pub fn contains(&self, card: &SpecificCard) -> bool {
+        self.first == *card || self.second == *card
+    }
+
+    /// True if the combo shares a card with any of `cards`.
+    pub fn conflicts_with(&self, cards: &[SpecificCard]) -> bool {
+        cards.iter().any(|card| self.contains(card))
+    }
+
+    pub fn overlaps(&self, other: &Combo) -> bool {
+        self.contains(&other.first) || self.contains(&other.second)
+    }
+}
+
+impl From<Combo> for Hand {
+    fn from(combo: Combo) -> Hand {
+        Hand {
+            first: combo.first.rank,
+            second: combo.second.rank,
+            suited: if combo.first.suit == combo.second.suit {
+                Suit::Suited
+            } else {
+                Suit::Off
+            },
+        }
+    }
+}
+
+impl From<Combo> for (&'static [u8], &'static[u8]) {
+    fn from(combo: Combo) -> (&'static [u8], &'static[u8]) {
+        (combo.first.svg_bytes(), combo.second.svg_bytes())
+    }
+}
+
+impl Hand {
+    /// The concrete combos making up this hand class: 6 for a pair, 4 suited
+    /// and 12 offsuit.
+    pub fn combos(&self) -> Vec<Combo> {
+        let high = Card::max(self.first, self.second);
+        let low = Card::min(self.first, self.second);
+        let mut combos = vec![];
+        for first_suit in DisplaySuit::iterator() {
+            for second_suit in DisplaySuit::iterator() {
+                let keep = if high == low {
+                    first_suit > second_suit
+                } else {
+                    match self.suited {
+                        Suit::Suited => first_suit == second_suit,
+                        Suit::Off => first_suit != second_suit,
+                    }
+                };
+                if keep {
+                    combos.push(Combo {
+                        first: SpecificCard::new(high, *first_suit),
+                        second: SpecificCard::new(low, *second_suit),
+                    });
+                }
+            }
+        }
+        combos
+    }
+
+    /// The combos of this hand that share no card with `dead`.
+    pub fn live_combos(&self, dead: &[SpecificCard]) -> Vec<Combo> {
+        self.combos()
+            .into_iter()
+            .filter(|combo| !combo.conflicts_with(dead))
+            .collect()
+    }
+}
+
+impl fmt::Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.first, self.second)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseComboError;
+
+impl FromStr for Combo {
+    type Err = ParseComboError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let first = s.get(0..2).and_then(|x| x.parse::<SpecificCard>().ok());
+        let second = s.get(2..4).and_then(|x| x.parse::<SpecificCard>().ok());
+        match (first, second, s.len()) {
+            (Some(first), Some(second), 4) => Combo::new(first, second).ok_or(ParseComboError),
+            _ => Err(ParseComboError)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+    use crate::card::SpecificCard;
+    use crate::hand::{Hand, Suit};
+    use super::Combo;
+
+    impl Arbitrary for Combo {
+        fn arbitrary<G: Gen>(g: &mut G) -> Combo {
+            loop {
+                let a = SpecificCard::arbitrary(g);
+                let b = SpecificCard::arbitrary(g);
+                if let Some(combo) = Combo::new(a, b) {
+                    return combo;
+                }
+            }
+        }
+    }
+
+    #[quickcheck]
+    fn parse_display_combo(combo: Combo) -> bool {
+        format!("{}", combo).parse::<Combo>().unwrap() == combo
+    }
+
+    #[quickcheck]
+    fn combos_belong_to_their_hand(hand: Hand) -> bool {
+        let normalized = Hand {
+            first: crate::card::Card::max(hand.first, hand.second),
+            second: crate::card::Card::min(hand.first, hand.second),
+            suited: hand.suited,
+        };
+        hand.combos().iter().all(|combo| Hand::from(*combo) == normalized)
+    }
+
+    #[test]
+    fn combo_counts_per_class() {
+        assert_eq!("AA".parse::<Hand>().unwrap().combos().len(), 6);
+        assert_eq!("AKs".parse::<Hand>().unwrap().combos().len(), 4);
+        assert_eq!("AKo".parse::<Hand>().unwrap().combos().len(), 12);
+    }
+
+    #[test]
+    fn parse_orders_cards() {
+        let combo = "KhAs".parse::<Combo>().unwrap();
+        assert_eq!(combo.to_string(), "AsKh");
+        assert_eq!(Hand::from(combo).suited, Suit::Off);
+        assert!("AsAs".parse::<Combo>().is_err());
+        assert!("AsK".parse::<Combo>().is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use iced::{button, Button, Container, Row, Text};
+
+use iced_native::{Align, Length};
+
+use crate::messages::Message;
+use crate::range::Range;
+use crate::styles;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    Save,
+    Discard,
+    Cancel,
+}
+
+/// Holds back a message that would lose unsaved edits or delete a range
+/// until the user decides what to do. Nothing else can be done in the
+/// meantime.
+pub struct Confirmation {
+    pub message: Message,
+    /// The ID of the range the confirmation is about.
+    pub range_id: String,
+    prompt: String,
+    is_delete: bool,
+    save_button: button::State,
+    discard_button: button::State,
+    cancel_button: button::State,
+}
+
+impl Confirmation {
+    pub fn unsaved(range: &Range, message: Message) -> Self {
+        Self::new(range, format!("\"{}\" has unsaved changes.", range.name), false, message)
+    }
+
+    pub fn delete(range: &Range, message: Message) -> Self {
+        Self::new(range, format!("Delete \"{}\"?", range.name), true, message)
+    }
+
+    fn new(range: &Range, prompt: String, is_delete: bool, message: Message) -> Self {
+        Self {
+            message,
+            range_id: range.id.clone(),
+            prompt,
+            is_delete,
+            save_button: button::State::new(),
+            discard_button: button::State::new(),
+            cancel_button: button::State::new(),
+        }
+    }
+
+    pub fn view(&mut self) -> Container<'_, Message> {
+        let mut row = Row::new()
+            .width(Length::Fill)
+            .align_items(Align::Center)
+            .spacing(8)
+            .push(Text::new(self.prompt.to_string()).width(Length::Fill));
+        if !self.is_delete {
+            row = row.push(
+                Button::new(&mut self.save_button, Text::new("Save"))
+                    .on_press(Message::ResolveConfirmation(Resolution::Save))
+                    .style(styles::Button::Basic),
+            );
+        }
+        row = row
+            .push(
+                Button::new(
+                    &mut self.discard_button,
+                    Text::new(if self.is_delete { "Delete" } else { "Discard" }),
+                )
+                .on_press(Message::ResolveConfirmation(Resolution::Discard))
+                .style(styles::Button::Basic),
+            )
+            .push(
+                Button::new(&mut self.cancel_button, Text::new("Cancel"))
+                    .on_press(Message::ResolveConfirmation(Resolution::Cancel))
+                    .style(styles::Button::Basic),
+            );
+        Container::new(row)
+            .style(styles::Container::Basic)
+            .width(Length::Fill)
+            .padding(8)
+    }
+}
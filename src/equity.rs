@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+use std::ops::AddAssign;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::card::SpecificCard;
+use crate::combo::Combo;
+use crate::evaluator::evaluate_holding;
+use crate::hand::Hand;
+use crate::range::Range;
+
+/// Share of showdowns won, tied and lost by hero. The fields accumulate
+/// showdown counts, so equities over different matchups can be added up.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Equity {
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+}
+
+impl Equity {
+    pub fn total(&self) -> f64 {
+        self.win + self.tie + self.lose
+    }
+
+    pub fn win_percent(&self) -> f32 {
+        self.percent_of(self.win)
+    }
+
+    pub fn tie_percent(&self) -> f32 {
+        self.percent_of(self.tie)
+    }
+
+    pub fn lose_percent(&self) -> f32 {
+        self.percent_of(self.lose)
+    }
+
+    /// Pot share in percent, counting ties as half a win.
+    pub fn equity_percent(&self) -> f32 {
+        self.percent_of(self.win + self.tie / 2.0)
+    }
+
+    /// Rescales the counts so they sum to `weight`.
+    pub fn normalized(&self, weight: f64) -> Equity {
+        let total = self.total();
+        if total == 0.0 {
+            return Equity::default();
+        }
+        Equity {
+            win: self.win / total * weight,
+            tie: self.tie / total * weight,
+            lose: self.lose / total * weight,
+        }
+    }
+
+    fn percent_of(&self, amount: f64) -> f32 {
+        let total = self.total();
+        if total == 0.0 {
+            0.0
+        } else {
+            (amount / total * 100.0) as f32
+        }
+    }
+}
+
+impl AddAssign for Equity {
+    fn add_assign(&mut self, other: Equity) {
+        self.win += other.win;
+        self.tie += other.tie;
+        self.lose += other.lose;
+    }
+}
+
+/// Equity of every hand class in hero's range against villain's range. Each
+/// class is weighted by its number of valid matchups, so the classes can be
+/// summed into the equity of any sub range.
+#[derive(Debug, Clone, Default)]
+pub struct RangeEquity {
+    pub per_hand: HashMap<Hand, Equity>,
+}
+
+impl RangeEquity {
+    pub fn overall_of<'a, I: Iterator<Item = &'a Hand>>(&self, hands: I) -> Equity {
+        let mut equity = Equity::default();
+        for hand in hands {
+            if let Some(hand_equity) = self.per_hand.get(hand) {
+                equity += *hand_equity;
+            }
+        }
+        equity
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    /// Largest number of showdowns that will be enumerated exactly before
+    /// falling back to Monte Carlo sampling.
+    pub exact_limit: u64,
+    pub trials: u32,
+    pub seed: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            exact_limit: 2_000_000,
+            trials: 20_000,
+            seed: 0,
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub fn hand_vs_hand(hero: Hand, villain: Hand, board: &[SpecificCard], settings: &Settings) -> Equity {
+    combos_vs_combos(&hero.combos(), &villain.combos(), board, settings)
+}
+
+pub fn hand_vs_range(hero: Hand, villain: &Range, board: &[SpecificCard], settings: &Settings) -> Equity {
+    let hero_combos = weighted(&hero.combos(), 1.0);
+    showdowns(&matchups(&hero_combos, &range_combos(villain), board), board, settings)
+}
+
+pub fn range_vs_range(hero: &Range, villain: &Range, board: &[SpecificCard], settings: &Settings) -> RangeEquity {
+    let villain_combos = range_combos(villain);
+    let mut per_hand = HashMap::new();
+    for (hand, weight) in hero.hands.iter() {
+        let matchups = matchups(&weighted(&hand.combos(), *weight), &villain_combos, board);
+        let equity = showdowns(&matchups, board, settings);
+        if equity.total() > 0.0 {
+            let matchup_weight = matchups.iter().map(|(_, _, w)| w).sum();
+            per_hand.insert(*hand, equity.normalized(matchup_weight));
+        }
+    }
+    RangeEquity { per_hand }
+}
+
+/// Equity of hero holding any of `hero` against villain holding any of
+/// `villain`, with every non conflicting pairing equally likely.
+pub fn combos_vs_combos(
+    hero: &[Combo],
+    villain: &[Combo],
+    board: &[SpecificCard],
+    settings: &Settings,
+) -> Equity {
+    showdowns(&matchups(&weighted(hero, 1.0), &weighted(villain, 1.0), board), board, settings)
+}
+
+fn weighted(combos: &[Combo], weight: f32) -> Vec<(Combo, f32)> {
+    combos.iter().map(|combo| (*combo, weight)).collect()
+}
+
+fn range_combos(range: &Range) -> Vec<(Combo, f32)> {
+    range
+        .hands
+        .iter()
+        .flat_map(|(hand, weight)| weighted(&hand.combos(), *weight))
+        .collect()
+}
+
+/// Every non conflicting pairing of hero and villain combos, weighted by how
+/// often both are played.
+fn matchups(hero: &[(Combo, f32)], villain: &[(Combo, f32)], board: &[SpecificCard]) -> Vec<(Combo, Combo, f64)> {
+    hero
+        .iter()
+        .filter(|(h, _)| !h.conflicts_with(board))
+        .flat_map(|(h, hero_weight)| {
+            villain
+                .iter()
+                .filter(move |(v, _)| !v.conflicts_with(board) && !v.overlaps(h))
+                .map(move |(v, villain_weight)| (*h, *v, (*hero_weight * *villain_weight) as f64))
+        })
+        .collect()
+}
+
+/// Runs every matchup to showdown, exactly if the number of runouts is small
+/// enough and by sampling otherwise.
+fn showdowns(matchups: &[(Combo, Combo, f64)], board: &[SpecificCard], settings: &Settings) -> Equity {
+    if matchups.is_empty() || board.len() > 5 {
+        return Equity::default();
+    }
+
+    let missing = 5 - board.len();
+    let showdowns = matchups.len() as u64 * choose(52 - 4 - board.len() as u64, missing as u64);
+    if showdowns <= settings.exact_limit {
+        enumerate(matchups, board)
+    } else {
+        sample(matchups, board, settings)
+    }
+}
+
+fn choose(n: u64, k: u64) -> u64 {
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+fn showdown(hero: &Combo, villain: &Combo, board: &[SpecificCard], weight: f64) -> Equity {
+    let hero_rank = evaluate_holding(hero, board);
+    let villain_rank = evaluate_holding(villain, board);
+    if hero_rank > villain_rank {
+        Equity { win: weight, ..Equity::default() }
+    } else if hero_rank < villain_rank {
+        Equity { lose: weight, ..Equity::default() }
+    } else {
+        Equity { tie: weight, ..Equity::default() }
+    }
+}
+
+fn enumerate(matchups: &[(Combo, Combo, f64)], board: &[SpecificCard]) -> Equity {
+    let mut equity = Equity::default();
+    for (hero, villain, weight) in matchups {
+        let available = SpecificCard::all()
+            .into_iter()
+            .filter(|card| !board.contains(card) && !hero.contains(card) && !villain.contains(card))
+            .collect::<Vec<SpecificCard>>();
+        let mut runout = board.to_vec();
+        for_each_runout(&available, 5 - board.len(), &mut runout, &mut |full_board| {
+            equity += showdown(hero, villain, full_board, *weight);
+        });
+    }
+    equity
+}
+
+fn for_each_runout<F: FnMut(&[SpecificCard])>(
+    available: &[SpecificCard],
+    missing: usize,
+    board: &mut Vec<SpecificCard>,
+    f: &mut F,
+) {
+    if missing == 0 {
+        f(board);
+        return;
+    }
+    for i in 0..available.len() {
+        board.push(available[i]);
+        for_each_runout(&available[i + 1..], missing - 1, board, f);
+        board.pop();
+    }
+}
+
+/// Samples matchups in proportion to their weight and deals random runouts.
+fn sample(matchups: &[(Combo, Combo, f64)], board: &[SpecificCard], settings: &Settings) -> Equity {
+    let cumulative = matchups
+        .iter()
+        .scan(0.0, |total, (_, _, weight)| {
+            *total += weight;
+            Some(*total)
+        })
+        .collect::<Vec<f64>>();
+    let total_weight = *cumulative.last().unwrap();
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+    let mut equity = Equity::default();
+    let mut runout = Vec::with_capacity(5);
+    for _ in 0..settings.trials {
+        let target = rng.gen::<f64>() * total_weight;
+        let index = match cumulative.binary_search_by(|c| c.partial_cmp(&target).unwrap()) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        };
+        let (hero, villain, _) = &matchups[index.min(matchups.len() - 1)];
+        let mut used = hero
+            .cards()
+            .iter()
+            .chain(villain.cards().iter())
+            .chain(board.iter())
+            .fold(0_u64, |used, card| used | 1 << card.index());
+        runout.clear();
+        runout.extend_from_slice(board);
+        while runout.len() < 5 {
+            let index = rng.gen_range(0, 52);
+            if used & (1 << index) == 0 {
+                used |= 1 << index;
+                runout.push(SpecificCard::from_index(index).unwrap());
+            }
+        }
+        equity += showdown(hero, villain, &runout, 1.0);
+    }
+    equity
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::card::SpecificCard;
+    use crate::combo::Combo;
+    use crate::hand::Hand;
+    use crate::range::Range;
+    use super::{combos_vs_combos, hand_vs_hand, hand_vs_range, range_vs_range, Settings};
+
+    fn cards(s: &str) -> Vec<SpecificCard> {
+        s.split_whitespace().map(|c| c.parse::<SpecificCard>().unwrap()).collect()
+    }
+
+    fn combo(s: &str) -> Combo {
+        s.parse::<Combo>().unwrap()
+    }
+
+    #[test]
+    fn river_is_decided() {
+        let equity = combos_vs_combos(&[combo("AhAd")], &[combo("KsKc")], &cards("2c 7d 9h Qs 3h"), &Settings::default());
+        assert_eq!(equity.win_percent(), 100.0);
+        assert_eq!(equity.total(), 1.0);
+    }
+
+    #[test]
+    fn turn_counts_outs_exactly() {
+        // Only the two remaining kings save villain.
+        let equity = combos_vs_combos(&[combo("AhAd")], &[combo("KsKc")], &cards("2c 7d 9h Qs"), &Settings::default());
+        assert_eq!(equity.total(), 44.0);
+        assert_eq!(equity.lose, 2.0);
+        assert_eq!(equity.win, 42.0);
+    }
+
+    #[test]
+    fn identical_hands_split() {
+        let equity = combos_vs_combos(&[combo("AhKh")], &[combo("AsKs")], &cards("2c 7d 9c"), &Settings::default());
+        assert!(equity.tie > equity.win + equity.lose);
+        assert_eq!(equity.win, equity.lose);
+    }
+
+    #[test]
+    fn aces_dominate_kings_preflop() {
+        let aces = "AA".parse::<Hand>().unwrap();
+        let kings = "KK".parse::<Hand>().unwrap();
+        let equity = hand_vs_hand(aces, kings, &[], &Settings::default());
+        assert!(equity.equity_percent() > 80.0 && equity.equity_percent() < 84.0);
+    }
+
+    #[test]
+    fn seeded_results_repeat() {
+        let hero = "AJo".parse::<Hand>().unwrap();
+        let mut villain = Range::new();
+        villain.toggle("QQ".parse::<Hand>().unwrap());
+        villain.toggle("AKs".parse::<Hand>().unwrap());
+        let settings = Settings { trials: 2_000, ..Settings::default() };
+        let first = hand_vs_range(hero, &villain, &[], &settings);
+        let second = hand_vs_range(hero, &villain, &[], &settings);
+        assert_eq!(first, second);
+        assert!(first.equity_percent() < 35.0);
+    }
+
+    #[test]
+    fn no_valid_matchups_is_empty() {
+        let equity = combos_vs_combos(&[combo("AhAd")], &[combo("AhKs")], &[], &Settings::default());
+        assert_eq!(equity.total(), 0.0);
+    }
+
+    #[test]
+    fn range_breakdown_sums_to_overall() {
+        let mut hero = Range::new();
+        hero.toggle("AA".parse::<Hand>().unwrap());
+        hero.toggle("72o".parse::<Hand>().unwrap());
+        let mut villain = Range::new();
+        villain.toggle("KK".parse::<Hand>().unwrap());
+        let board = cards("2c 7d 9h Qs");
+        let result = range_vs_range(&hero, &villain, &board, &Settings::default());
+
+        let aces = result.per_hand[&"AA".parse::<Hand>().unwrap()];
+        let two_pair = result.per_hand[&"72o".parse::<Hand>().unwrap()];
+        // Six combos of aces and the seven 72o combos not using 7d or 2c,
+        // each against the six combos of kings.
+        assert_eq!(aces.total(), 36.0);
+        assert_eq!(two_pair.total(), 42.0);
+        assert!(aces.equity_percent() > 90.0);
+        assert!(two_pair.equity_percent() > 75.0);
+
+        let overall = result.overall_of(result.per_hand.keys());
+        assert!((overall.total() - 78.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn villain_weights_shift_equity() {
+        let hero = "QQ".parse::<Hand>().unwrap();
+        let mut villain = Range::new();
+        villain.toggle("AA".parse::<Hand>().unwrap());
+        villain.toggle("72o".parse::<Hand>().unwrap());
+        let settings = Settings { trials: 4_000, ..Settings::default() };
+        let full = hand_vs_range(hero, &villain, &[], &settings);
+        villain.set_weight("AA".parse::<Hand>().unwrap(), 0.1);
+        let mixed = hand_vs_range(hero, &villain, &[], &settings);
+        assert!(mixed.equity_percent() > full.equity_percent() + 10.0);
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt;
+use serde::{Serialize, Deserialize};
+
+use crate::card::SpecificCard;
+use crate::combo::Combo;
+
+#[derive(
+    PartialEq, Eq, Hash, Serialize, Deserialize, Ord,
+    PartialOrd, Copy, Clone, Debug
+    )
+]
+pub enum HandCategory {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl fmt::Display for HandCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            HandCategory::HighCard => "High card",
+            HandCategory::Pair => "Pair",
+            HandCategory::TwoPair => "Two pair",
+            HandCategory::ThreeOfAKind => "Three of a kind",
+            HandCategory::Straight => "Straight",
+            HandCategory::Flush => "Flush",
+            HandCategory::FullHouse => "Full house",
+            HandCategory::FourOfAKind => "Four of a kind",
+            HandCategory::StraightFlush => "Straight flush",
+        })
+    }
+}
+
+/// Strength of the best five card hand in a holding. Larger is better and
+/// equal ranks split the pot.
+///
+/// The category sits above bit 20, followed by up to five 4 bit rank
+/// indices (`0` for a deuce, `12` for an ace) in order of significance.
+#[derive(PartialEq, Eq, Hash, Ord, PartialOrd, Copy, Clone, Debug)]
+pub struct HandRank(u32);
+
+impl HandRank {
+    fn new(category: HandCategory) -> Self {
+        HandRank((category as u32) << 20)
+    }
+
+    fn then_at(self, slot: usize, rank: u8) -> Self {
+        HandRank(self.0 | (rank as u32) << (16 - 4 * slot))
+    }
+
+    #[allow(dead_code)]
+    pub fn category(&self) -> HandCategory {
+        match self.0 >> 20 {
+            0 => HandCategory::HighCard,
+            1 => HandCategory::Pair,
+            2 => HandCategory::TwoPair,
+            3 => HandCategory::ThreeOfAKind,
+            4 => HandCategory::Straight,
+            5 => HandCategory::Flush,
+            6 => HandCategory::FullHouse,
+            7 => HandCategory::FourOfAKind,
+            _ => HandCategory::StraightFlush,
+        }
+    }
+}
+
+/// Ranks the best five card hand that can be made from `hole` and `board`.
+pub fn evaluate_holding(hole: &Combo, board: &[SpecificCard]) -> HandRank {
+    let mut suits = suit_masks(board);
+    for card in hole.cards().iter() {
+        suits[u8::from(card.suit) as usize] |= 1 << (u8::from(card.rank) - 2);
+    }
+    evaluate_suit_masks(&suits)
+}
+
+/// Ranks the best five card hand among `cards`, which should hold between
+/// five and seven distinct cards.
+#[allow(dead_code)]
+pub fn evaluate(cards: &[SpecificCard]) -> HandRank {
+    debug_assert!(cards.len() >= 5 && cards.len() <= 7);
+    evaluate_suit_masks(&suit_masks(cards))
+}
+
+fn suit_masks(cards: &[SpecificCard]) -> [u16; 4] {
+    let mut suits = [0_u16; 4];
+    for card in cards {
+        suits[u8::from(card.suit) as usize] |= 1 << (u8::from(card.rank) - 2);
+    }
+    suits
+}
+
+/// Rank index of the highest card of the best straight in `mask`, counting
+/// the ace as low for the wheel.
+fn straight_high(mask: u16) -> Option<u8> {
+    let shifted = ((mask as u32) << 1) | ((mask as u32 >> 12) & 1);
+    let runs = shifted & (shifted >> 1) & (shifted >> 2) & (shifted >> 3) & (shifted >> 4);
+    if runs == 0 {
+        None
+    } else {
+        Some((31 - runs.leading_zeros()) as u8 + 3)
+    }
+}
+
+fn highest(mask: u16) -> u8 {
+    (15 - mask.leading_zeros()) as u8
+}
+
+/// Fills tiebreaker slots `slot..slot + count` with the highest ranks in `mask`.
+fn with_top_ranks(mut rank: HandRank, mut mask: u16, slot: usize, count: usize) -> HandRank {
+    for i in slot..slot + count {
+        if mask == 0 {
+            break;
+        }
+        let high = highest(mask);
+        rank = rank.then_at(i, high);
+        mask &= !(1 << high);
+    }
+    rank
+}
+
+fn evaluate_suit_masks(suits: &[u16; 4]) -> HandRank {
+    for suit in suits.iter() {
+        if suit.count_ones() >= 5 {
+            return match straight_high(*suit) {
+                Some(high) => HandRank::new(HandCategory::StraightFlush).then_at(0, high),
+                None => with_top_ranks(HandRank::new(HandCategory::Flush), *suit, 0, 5),
+            };
+        }
+    }
+
+    let all = suits[0] | suits[1] | suits[2] | suits[3];
+    let mut quads = 0_u16;
+    let mut trips = 0_u16;
+    let mut pairs = 0_u16;
+    for rank in 0..13 {
+        let bit = 1 << rank;
+        match suits.iter().filter(|suit| *suit & bit != 0).count() {
+            4 => quads |= bit,
+            3 => trips |= bit,
+            2 => pairs |= bit,
+            _ => {}
+        }
+    }
+
+    if quads != 0 {
+        let quad = highest(quads);
+        let rank = HandRank::new(HandCategory::FourOfAKind).then_at(0, quad);
+        return with_top_ranks(rank, all & !(1 << quad), 1, 1);
+    }
+
+    if trips != 0 {
+        let trip = highest(trips);
+        let rest = (trips & !(1 << trip)) | pairs;
+        if rest != 0 {
+            return HandRank::new(HandCategory::FullHouse)
+                .then_at(0, trip)
+                .then_at(1, highest(rest));
+        }
+    }
+
+    if let Some(high) = straight_high(all) {
+        return HandRank::new(HandCategory::Straight).then_at(0, high);
+    }
+
+    if trips != 0 {
+        let trip = highest(trips);
+        let rank = HandRank::new(HandCategory::ThreeOfAKind).then_at(0, trip);
+        return with_top_ranks(rank, all & !(1 << trip), 1, 2);
+    }
+
+    if pairs.count_ones() >= 2 {
+        let first = highest(pairs);
+        let second = highest(pairs & !(1 << first));
+        let rank = HandRank::new(HandCategory::TwoPair)
+            .then_at(0, first)
+            .then_at(1, second);
+        return with_top_ranks(rank, all & !(1 << first) & !(1 << second), 2, 1);
+    }
+
+    if pairs != 0 {
+        let pair = highest(pairs);
+        let rank = HandRank::new(HandCategory::Pair).then_at(0, pair);
+        return with_top_ranks(rank, all & !(1 << pair), 1, 3);
+    }
+
+    with_top_ranks(HandRank::new(HandCategory::HighCard), all, 0, 5)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use quickcheck_macros::quickcheck;
+    use itertools::Itertools;
+    use crate::card::SpecificCard;
+    use crate::combo::Combo;
+    use super::{evaluate, evaluate_holding, HandCategory};
+
+    fn cards(s: &str) -> Vec<SpecificCard> {
+        s.split_whitespace().map(|c| c.parse::<SpecificCard>().unwrap()).collect()
+    }
+
+    #[test]
+    fn five_card_category_frequencies() {
+        let deck = SpecificCard::all();
+        let mut counts = HashMap::<HandCategory, u32>::new();
+        let mut distinct = HashSet::new();
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            let rank = evaluate(&[deck[a], deck[b], deck[c], deck[d], deck[e]]);
+                            *counts.entry(rank.category()).or_insert(0) += 1;
+                            distinct.insert(rank);
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(counts[&HandCategory::StraightFlush], 40);
+        assert_eq!(counts[&HandCategory::FourOfAKind], 624);
+        assert_eq!(counts[&HandCategory::FullHouse], 3_744);
+        assert_eq!(counts[&HandCategory::Flush], 5_108);
+        assert_eq!(counts[&HandCategory::Straight], 10_200);
+        assert_eq!(counts[&HandCategory::ThreeOfAKind], 54_912);
+        assert_eq!(counts[&HandCategory::TwoPair], 123_552);
+        assert_eq!(counts[&HandCategory::Pair], 1_098_240);
+        assert_eq!(counts[&HandCategory::HighCard], 1_302_540);
+        assert_eq!(distinct.len(), 7_462);
+    }
+
+    #[test]
+    fn known_orderings() {
+        let wheel = evaluate(&cards("Ah 2c 3d 4s 5h"));
+        let six_high = evaluate(&cards("6h 2c 3d 4s 5h"));
+        let broadway = evaluate(&cards("Ah Kc Qd Js Th"));
+        assert_eq!(wheel.category(), HandCategory::Straight);
+        assert!(wheel < six_high && six_high < broadway);
+
+        let aces_up = evaluate(&cards("Ah Ad 2c 2d Kh"));
+        let kings_up = evaluate(&cards("Kh Kd Qc Qd Ah"));
+        assert!(kings_up < aces_up);
+
+        let steel_wheel = evaluate(&cards("As 2s 3s 4s 5s"));
+        let quads = evaluate(&cards("Ac Ad Ah As Ks"));
+        assert_eq!(steel_wheel.category(), HandCategory::StraightFlush);
+        assert!(quads < steel_wheel);
+
+        assert_eq!(evaluate(&cards("Ah Kh Qc Jd 9s")), evaluate(&cards("Ad Ks Qh Jc 9c")));
+    }
+
+    #[test]
+    fn holding_plays_the_board() {
+        let board = cards("Ah Kh Qh Jh Th");
+        let hole = "2c3d".parse::<Combo>().unwrap();
+        assert_eq!(evaluate_holding(&hole, &board), evaluate(&board));
+
+        let board = cards("9c 9d 4h 4s 2c");
+        let better = "AsKs".parse::<Combo>().unwrap();
+        let worse = "QsJs".parse::<Combo>().unwrap();
+        assert!(evaluate_holding(&worse, &board) < evaluate_holding(&better, &board));
+        assert_eq!(evaluate_holding(&worse, &board).category(), HandCategory::TwoPair);
+    }
+
+    #[quickcheck]
+    fn seven_cards_rank_as_best_five(seed: Vec<u8>) -> bool {
+        let mut indices = seed.iter().map(|x| x % 52).unique().collect::<Vec<u8>>();
+        if indices.len() < 7 {
+            return true;
+        }
+        indices.truncate(7);
+        let seven = indices.iter().map(|i| SpecificCard::from_index(*i).unwrap()).collect::<Vec<_>>();
+        let best = seven.iter().cloned().combinations(5).map(|five| evaluate(&five)).max().unwrap();
+        evaluate(&seven) == best
+    }
+}
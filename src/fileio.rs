@@ -1,42 +1,85 @@
 use crate::range;
+use crate::range_file;
+use crate::settings;
+use crate::strategy;
 use crate::review;
 use directories::ProjectDirs;
 use serde::de::{MapAccess, Visitor};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
-use std::io::Write;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 
 const TLD: &'static str = "com";
 const ORG: &'static str = "Marble Devices";
 const APP: &'static str = "Range Trainer";
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct ReviewCollection(
-    HashMap<(review::item::Presentation, review::item::Answer), review::item::ReviewItem>,
-);
+type LegacyKey = (review::item::Presentation, review::item::Answer);
+
+/// Review items keyed by the ID of their question.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ReviewCollection {
+    items: HashMap<review::item::QuestionId, review::item::ReviewItem>,
+    /// Items from files saved before questions had IDs, still under their old
+    /// `(Presentation, Answer)` keys. They are picked up by the questions they
+    /// belong to when review items are rebuilt, and saved under their old
+    /// keys until then.
+    legacy: HashMap<LegacyKey, review::item::ReviewItem>,
+}
 
 impl ReviewCollection {
     pub fn new() -> Self {
-        Self(HashMap::<(review::item::Presentation, review::item::Answer), review::item::ReviewItem>::new())
+        Self {
+            items: HashMap::new(),
+            legacy: HashMap::new(),
+        }
     }
 
-    pub fn get(&self, key: &(review::item::Presentation, review::item::Answer)) -> Option<&review::item::ReviewItem>{
-        self.0.get(key)
+    pub fn get(&self, key: &review::item::QuestionId) -> Option<&review::item::ReviewItem>{
+        self.items.get(key)
     }
 
-    pub fn get_mut(&mut self, key: &(review::item::Presentation, review::item::Answer)) -> Option<&mut review::item::ReviewItem>{
-        self.0.get_mut(key)
+    pub fn get_mut(&mut self, key: &review::item::QuestionId) -> Option<&mut review::item::ReviewItem>{
+        self.items.get_mut(key)
     }
 
-    pub fn insert(&mut self, key: (review::item::Presentation, review::item::Answer), value: review::item::ReviewItem) -> Option<review::item::ReviewItem> {
-        self.0.insert(key, value)
+    pub fn insert(&mut self, key: review::item::QuestionId, value: review::item::ReviewItem) -> Option<review::item::ReviewItem> {
+        self.items.insert(key, value)
     }
 
-    pub fn iter(&self) -> std::collections::hash_map::Iter<(review::item::Presentation, review::item::Answer), review::item::ReviewItem> {
-        self.0.iter()
+    pub fn iter(&self) -> std::collections::hash_map::Iter<'_, review::item::QuestionId, review::item::ReviewItem> {
+        self.items.iter()
+    }
+
+    pub fn values_mut(&mut self) -> std::collections::hash_map::ValuesMut<'_, review::item::QuestionId, review::item::ReviewItem> {
+        self.items.values_mut()
+    }
+
+    /// An item saved before questions had IDs, by the wording and answer it
+    /// was saved with.
+    pub fn legacy(&self, key: &LegacyKey) -> Option<&review::item::ReviewItem> {
+        self.legacy.get(key)
+    }
+
+    pub fn has_legacy(&self) -> bool {
+        !self.legacy.is_empty()
+    }
+
+    /// An empty collection that keeps this one's legacy items.
+    pub fn legacy_only(&self) -> Self {
+        Self {
+            items: HashMap::new(),
+            legacy: self.legacy.clone(),
+        }
+    }
+
+    /// Drops a legacy item once a question has taken over its history.
+    pub fn remove_legacy(&mut self, key: &LegacyKey) {
+        self.legacy.remove(key);
     }
 }
 
@@ -45,7 +88,12 @@ impl Serialize for ReviewCollection {
     where
         S: Serializer,
     {
-        serializer.collect_map(self.0.iter().map(|(k, v)| (serialize_review_collection_key(k), v)))
+        serializer.collect_map(
+            self.items
+                .iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .chain(self.legacy.iter().map(|(k, v)| (serialize_review_collection_key(k), v))),
+        )
     }
 }
 
@@ -67,16 +115,13 @@ impl<'de> Deserialize<'de> for ReviewCollection {
             where
                 M: MapAccess<'de>,
             {
-                let mut review_collection = ReviewCollection(HashMap::<
-                    (review::item::Presentation, review::item::Answer),
-                    review::item::ReviewItem,
-                >::with_capacity(
-                    access.size_hint().unwrap_or(0)
-                ));
+                let mut review_collection = ReviewCollection::new();
 
                 while let Some((key, value)) = access.next_entry::<String,review::item::ReviewItem>()? {
-                    if let Some(key) = parse_review_collection_key(&key) {
-                         review_collection.0.insert(key, value);
+                    if !value.question.id.0.is_empty() {
+                        review_collection.items.insert(review::item::QuestionId(key), value);
+                    } else if let Some(key) = parse_review_collection_key(&key) {
+                         review_collection.legacy.insert(key, value);
                     } else {
                         return Err(serde::de::Error::custom("failed parsing review collection key"));
                     }
@@ -109,91 +154,174 @@ fn parse_review_collection_key(s: &str) -> Option<(review::item::Presentation, r
     }
 }
 
+/// How keys were written before questions had IDs.
 fn serialize_review_collection_key(k:&(review::item::Presentation, review::item::Answer)) -> String {
         format!("{}⦙{}", k.0, k.1).to_string()
 }
 
-pub fn load_review_items(
-) -> ReviewCollection {
-    if let Some(proj_dirs) = ProjectDirs::from(TLD, ORG, APP) {
-        let data_dir = proj_dirs.data_dir().join("review_items.json");
-        let path = data_dir.as_path();
-        if let Ok(contents) = fs::read_to_string(path) {
-            if let Ok(data) = serde_json::from_str(&contents) {
-                return data;
-            }
-        }
-    }
-    return ReviewCollection::new();
+pub fn load_review_items() -> Result<ReviewCollection, Box<dyn Error>> {
+    load_json("review_items.json")
 }
 
-pub fn save_review_items(review_items: &ReviewCollection,
-) -> Result<(), Box<dyn Error>> {
-    if let Some(proj_dirs) = ProjectDirs::from("com", "Marble Devices", "Range Trainer") {
-        fs::create_dir_all(proj_dirs.data_dir())?;
-        let data_dir = proj_dirs.data_dir().join("review_items.json");
-        let path = data_dir.as_path();
-        let mut file = fs::OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .create(true)
-            .open(path)?;
-
-        file.write_all(&serde_json::to_string(review_items)?.as_bytes())?;
-        Ok(())
-    } else {
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Unable to create app directory.",
-        )))
+pub fn save_review_items(review_items: &ReviewCollection) -> Result<(), Box<dyn Error>> {
+    save_json("review_items.json", review_items)
+}
+
+/// Adds an answer to the end of the review log, which sits next to
+/// `review_items.json`.
+pub fn append_review_log(entry: &review::log::ReviewLogEntry) -> Result<(), Box<dyn Error>> {
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(data_dir()?.join("review_log.jsonl"))?;
+
+    let mut log_end = None;
+    if file.metadata()?.len() > 0 {
+        let mut byte = [0_u8];
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut byte)?;
+        log_end = Some(byte[0]);
     }
+    file.write_all(review::log::to_line_after(log_end, entry)?.as_bytes())?;
+    Ok(())
 }
 
-pub fn load_ranges() -> Vec<range::Range> {
+/// Every answer in the review log, oldest first.
+pub fn load_review_log() -> Vec<review::log::ReviewLogEntry> {
     if let Some(proj_dirs) = ProjectDirs::from(TLD, ORG, APP) {
-        let data_dir = proj_dirs.data_dir().join("ranges.json");
+        let data_dir = proj_dirs.data_dir().join("review_log.jsonl");
         let path = data_dir.as_path();
         if let Ok(contents) = fs::read_to_string(path) {
-            if let Ok(data) = serde_json::from_str(&contents) {
-                return data;
-            } else {
-                dbg!("Couldn't parse json");
-            }
-        } else {
-            dbg!("Couldnt read contents");
+            return review::log::parse(&contents);
         }
-    } else {
-        dbg!("couldnt get project dir");
     }
-    return vec![];
+    vec![]
+}
+
+pub fn load_ranges() -> Result<Vec<range::Range>, Box<dyn Error>> {
+    load_json("ranges.json")
 }
 
 pub fn save_ranges(ranges: &Vec<range::Range>) -> Result<(), Box<dyn Error>> {
-    if let Some(proj_dirs) = ProjectDirs::from("com", "Marble Devices", "Range Trainer") {
-        fs::create_dir_all(proj_dirs.data_dir())?;
-        let data_dir = proj_dirs.data_dir().join("ranges.json");
-        let path = data_dir.as_path();
-        let mut file = fs::OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .create(true)
-            .open(path)?;
-
-        file.write_all(&serde_json::to_string(ranges)?.as_bytes())?;
-        Ok(())
+    save_json("ranges.json", ranges)
+}
+
+pub fn load_strategies() -> Result<Vec<strategy::Strategy>, Box<dyn Error>> {
+    load_json("strategies.json")
+}
+
+pub fn save_strategies(strategies: &Vec<strategy::Strategy>) -> Result<(), Box<dyn Error>> {
+    save_json("strategies.json", strategies)
+}
+
+pub fn load_settings() -> Result<settings::Settings, Box<dyn Error>> {
+    load_json("settings.json")
+}
+
+pub fn save_settings(settings: &settings::Settings) -> Result<(), Box<dyn Error>> {
+    save_json("settings.json", settings)
+}
+
+/// The app's data directory, created if it doesn't exist yet.
+fn data_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let proj_dirs = ProjectDirs::from(TLD, ORG, APP).ok_or_else(|| io::Error::other("Unable to create app directory."))?;
+    fs::create_dir_all(proj_dirs.data_dir())?;
+    Ok(proj_dirs.data_dir().to_path_buf())
+}
+
+/// Reads `file_name` from the data directory, or the default if it hasn't
+/// been saved yet.
+fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> Result<T, Box<dyn Error>> {
+    let path = data_dir()?.join(file_name);
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let contents = fs::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(|e| format!("{}: {}", file_name, e).into())
+}
+
+/// Replaces `file_name` in the data directory with `data`.
+fn save_json<T: Serialize + ?Sized>(file_name: &str, data: &T) -> Result<(), Box<dyn Error>> {
+    fs::write(data_dir()?.join(file_name), serde_json::to_string(data)?)?;
+    Ok(())
+}
+
+/// Writes a single range to `path` in `format`.
+pub fn export_range(path: &Path, range: &range::Range, format: range_file::RangeFormat) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, range_file::format(range, format))?;
+    Ok(())
+}
+
+/// Writes an SVG chart to `path`.
+pub fn export_chart(path: &Path, svg: &str) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, svg)?;
+    Ok(())
+}
+
+/// Writes every range to its own file under `dir`, with a subfolder for
+/// each of the library's folders.
+pub fn export_library(dir: &Path, ranges: &[range::Range], format: range_file::RangeFormat) -> Result<(), Box<dyn Error>> {
+    for (range, path) in ranges.iter().zip(range_file::library_paths(ranges, format)) {
+        export_range(&dir.join(path), range, format)?;
+    }
+    Ok(())
+}
+
+/// Reads a range file, or every range file under a folder. Ranges are named
+/// after their files and keep the subfolders they were found in.
+pub fn import_ranges(path: &Path) -> Result<Vec<range::Range>, Box<dyn Error>> {
+    let mut ranges = vec![];
+    if path.is_dir() {
+        import_folder(path, "", &mut ranges)?;
     } else {
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Unable to create app directory.",
-        )))
+        ranges.push(import_range(path, "")?);
+    }
+    Ok(ranges)
+}
+
+fn import_folder(dir: &Path, folder: &str, ranges: &mut Vec<range::Range>) -> Result<(), Box<dyn Error>> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, std::io::Error>>()?;
+    entries.sort();
+    for entry in entries {
+        let name = entry.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        if entry.is_dir() {
+            let subfolder = if folder.is_empty() { name } else { format!("{}/{}", folder, name) };
+            import_folder(&entry, &subfolder, ranges)?;
+        } else if range_file::RangeFormat::iterator()
+            .any(|format| entry.extension().and_then(|extension| extension.to_str()) == Some(format.extension()))
+        {
+            ranges.push(import_range(&entry, folder)?);
+        }
     }
+    Ok(())
+}
+
+fn import_range(path: &Path, folder: &str) -> Result<range::Range, Box<dyn Error>> {
+    let hands = range_file::parse(&fs::read_to_string(path)?)?;
+    Ok(range::Range {
+        name: path
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("imported range")),
+        hands,
+        folder: folder.to_string(),
+        ..range::Range::new()
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use crate::review;
     use super::ReviewCollection;
-    use std::collections::HashMap;
     use quickcheck::{Arbitrary, Gen};
     use quickcheck_macros::quickcheck;
     use chrono::prelude::*;
@@ -265,16 +393,19 @@ mod tests {
     impl Arbitrary for ReviewCollection {
         fn arbitrary<G: Gen>(g: &mut G) -> ReviewCollection {
             let size = g.next_u32() % 50;
-            let mut review_collection = ReviewCollection(HashMap::<(review::item::Presentation, review::item::Answer), review::item::ReviewItem>::new());
-            for _ in  0..size {
+            let mut review_collection = ReviewCollection::new();
+            for i in  0..size {
+                let id = review::item::QuestionId(format!("question/{}", i));
                 let presentation = review::item::Presentation::arbitrary(g);
                 let answer = review::item::Answer::arbitrary(g);
-                review_collection.0.insert((presentation.clone(), answer.clone()), review::item::ReviewItem {
+                review_collection.items.insert(id.clone(), review::item::ReviewItem {
                     difficulty: (g.next_u32() % 100) as f32 / 100.0,
                     days_between_review_attempts: f32::arbitrary(g),
                     date_last_reviewed: Option::<MyDateTimeLocal>::arbitrary(g).map(|x| x.0),
                     last_answer_correct: bool::arbitrary(g),
+                    scheduler_state: Default::default(),
                     question: review::item::Question {
+                        id,
                         presentation: presentation,
                         options: review::item::Options::arbitrary(g),
                         answer: answer,
@@ -286,6 +417,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn exported_libraries_import_with_their_folders() {
+        let dir = std::env::temp_dir().join(format!("range_trainer_library_{}", std::process::id()));
+        let mut open = crate::range::Range::new();
+        open.name = String::from("UTG open");
+        open.folder = String::from("6-max/Cash");
+        open.set_weight("AKo".parse().unwrap(), 0.5);
+        let ranges = vec![open, crate::range::Range::from_percent(10)];
+        for format in crate::range_file::RangeFormat::iterator() {
+            super::export_library(&dir, &ranges, *format).unwrap();
+            let imported = super::import_ranges(&dir).unwrap();
+            std::fs::remove_dir_all(&dir).unwrap();
+            assert_eq!(imported.len(), 2);
+            assert_eq!(imported[0].folder, "6-max/Cash");
+            assert_eq!(imported[0].name, "UTG open");
+            assert_eq!(imported[0].hands, ranges[0].hands);
+            assert_eq!(imported[1].hands, ranges[1].hands);
+        }
+    }
+
+    #[test]
+    fn legacy_review_items_load_under_their_old_keys() {
+        let key = (
+            review::item::Presentation::TextHand(review::item::DisplayString::new("UTG open"), "AKs".parse().unwrap()),
+            review::item::Answer::Yes,
+        );
+        let json = format!(
+            r#"{{"{}":{{"difficulty":0.3,"days_between_review_attempts":3.0,"date_last_reviewed":null,"last_answer_correct":true,"question":{}}}}}"#,
+            super::serialize_review_collection_key(&key),
+            r#"{"presentation":{"TextHand":["UTG open",{"first":"Ace","second":"King","suited":"Suited"}]},"options":"Binary","answer":"Yes","tolerance":null}"#
+        );
+        let review_collection: ReviewCollection = serde_json::from_str(&json).unwrap();
+        assert!(review_collection.has_legacy());
+        assert!(review_collection.legacy(&key).unwrap().last_answer_correct);
+        assert_eq!(review_collection.iter().count(), 0);
+        let saved: ReviewCollection = serde_json::from_str(&serde_json::to_string(&review_collection).unwrap()).unwrap();
+        assert_eq!(saved, review_collection);
+
+        let mut rebuilt = review_collection.legacy_only();
+        rebuilt.remove_legacy(&key);
+        assert!(!rebuilt.has_legacy());
+    }
+
     #[quickcheck]
     fn deserialized_serialized_reivew_collection_key_is_unchanged(key: (review::item::Presentation, review::item::Answer)) -> bool {
         super::parse_review_collection_key(&super::serialize_review_collection_key(&key)).unwrap() == key
@@ -48,12 +48,24 @@ impl Hand {
         points
     }
 
+    /// How many of the 1326 starting combos belong to this hand class.
+    pub fn combo_count(&self) -> u8 {
+        if self.first == self.second {
+            6
+        } else {
+            match self.suited {
+                Suit::Suited => 4,
+                Suit::Off => 12,
+            }
+        }
+    }
+
     pub fn all() -> Vec<Hand> {
         let cards = vec![Card::Two, Card::Three, Card::Four, Card::Five, Card::Six, Card::Seven, Card::Eight,
         Card::Nine, Card::Ten, Card::Jack, Card::Queen, Card::King, Card::Ace];
         let mut hands: Vec<Hand> = vec![];
         for first in (0..13).rev() {
-            for second in (0..first).rev() {
+            for second in (0..=first).rev() {
                 if first != second {
                     hands.push(Hand{first: cards[first], second: cards[second], suited:Suit::Suited})
                 }
@@ -212,4 +224,18 @@ mod tests {
     fn parse_display_hand(hand: super::Hand) -> bool {
         format!("{}", hand).parse::<super::Hand>().unwrap() == hand
     }
+
+    #[test]
+    fn all_hands_include_the_pairs() {
+        let hands = super::Hand::all();
+        assert_eq!(hands.len(), 169);
+        assert_eq!(hands.iter().filter(|h| h.first == h.second).count(), 13);
+    }
+
+    #[test]
+    fn all_hands_cover_every_combo() {
+        let hands = super::Hand::all();
+        assert_eq!(hands.len(), 169);
+        assert_eq!(hands.iter().map(|h| h.combo_count() as u32).sum::<u32>(), 1326);
+    }
 }
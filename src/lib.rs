@@ -1,14 +1,29 @@
 mod hand;
 mod range;
+mod metadata;
 mod card;
+mod combo;
+mod evaluator;
+mod equity;
+mod notation;
+mod ordering;
+mod strategy;
+mod settings;
 mod fileio;
+mod range_file;
+mod chart;
 mod range_trainer;
 mod toolbar;
+mod confirmation;
 mod ranges_screen;
 mod study_screen;
+mod statistics;
+mod statistics_screen;
 mod messages;
+mod shortcuts;
 mod svg;
 mod styles;
 mod review;
 
 pub use crate::range_trainer::RangeTrainer;
+pub use crate::chart::export_saved_chart;
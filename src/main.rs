@@ -1,6 +1,25 @@
+use std::path::Path;
+use std::process;
+
 use iced::{Settings, Application};
-use range_trainer::RangeTrainer;
+use range_trainer::{export_saved_chart, RangeTrainer};
 
 fn main() {
-    RangeTrainer::run(Settings::default());
+    let args = std::env::args().collect::<Vec<String>>();
+    match args.get(1).map(|arg| arg.as_str()) {
+        // Render a saved chart to SVG without opening a window.
+        Some("chart") => match (args.get(2), args.get(3)) {
+            (Some(name), Some(path)) => {
+                if let Err(e) = export_saved_chart(name, Path::new(path)) {
+                    eprintln!("Could not export chart: {}", e);
+                    process::exit(1);
+                }
+            }
+            _ => {
+                eprintln!("Usage: range_trainer chart <range or strategy name> <output.svg>");
+                process::exit(2);
+            }
+        },
+        _ => RangeTrainer::run(Settings::default()),
+    }
 }
@@ -1,29 +1,82 @@
+use crate::confirmation::Resolution;
+use crate::equity::RangeEquity;
 use crate::hand::Hand;
-use crate::range::Range;
-use crate::ranges_screen::ActiveRange;
+use crate::metadata::RangeMetadata;
+use crate::ordering::OrderingKind;
+use crate::range::{Range, RangeOperation};
+use crate::range_file::RangeFormat;
+use crate::ranges_screen::{ActiveRange, ActiveStrategy, MatrixInput, StrategyBrush};
 use crate::review;
+use crate::review::scheduler::{Grade, SchedulerKind};
+use crate::shortcuts::Shortcut;
+use crate::strategy::Strategy;
 
 #[derive(Debug, Clone,)]
 pub enum Message {
     SaveRanges,
     ViewStudyScreen,
     ViewRangesScreen,
+    ViewStatisticsScreen,
     RangesScreen(RangesMessage),
     CreateNewRange,
     RangeSelected(usize),
     SaveRange(Option<ActiveRange>),
     CopyRange(Option<ActiveRange>),
     DeleteRange(Option<ActiveRange>),
+    MoveRangeUp(usize),
+    MoveRangeDown(usize),
+    ImportRanges(String),
+    ExportRange(ActiveRange, String, RangeFormat),
+    ExportLibrary(String, RangeFormat),
+    ExportChart(String),
+    SelectHandOrdering(OrderingKind),
+    ComputeRangeEquity(ActiveRange, String),
+    EquityAnswersComputed(String, u64, Vec<(Hand, f32)>),
+    CombineRanges(ActiveRange, String, RangeOperation),
+    CompareRanges(String),
+    CreateNewStrategy,
+    StrategySelected(usize),
+    SaveStrategy(Option<ActiveStrategy>),
+    DeleteStrategy(Option<ActiveStrategy>),
+    Shortcut(Shortcut),
+    ResolveConfirmation(Resolution),
     RequestNewQuestion,
+    SelectScheduler(SchedulerKind),
     AnswerReviewItem(crate::range_trainer::ReviewItemsKey, review::item::Answer),
+    GradeReviewItem(crate::range_trainer::ReviewItemsKey, Grade),
     ReviewDisplayMessage(review::display::ReviewDisplayMessage),
 }
 
 #[derive(Debug, Clone)]
 pub enum RangesMessage {
-    ToggleHand(Hand),
+    MatrixInput(MatrixInput),
     RangeNameChanged(String),
+    NotationChanged(String),
+    BrushWeightChanged(f32),
     UpdateSelectRangeButtons(Vec<Range>),
     RangesHaveBeenSaved,
+    CycleCompareRange,
+    RangeEquityComputed(usize, RangeEquity),
+    InvertRange,
+    MetadataChanged(RangeMetadata),
+    FolderChanged(String),
+    TagsChanged(String),
+    SearchChanged(String),
+    FilePathChanged(String),
+    CycleFileFormat,
+    FileStatus(String),
+    HandOrderingSelected(OrderingKind, String),
+    HandOrderComputed(String, Vec<Hand>),
+    TopPercentChanged(f32),
+    Undo,
+    Redo,
+    ComparisonRangeLoaded(Range),
+    CloseComparison,
+    RangesCombined(usize, Range),
+    StrategyNameChanged(String),
+    StrategyBrushSelected(StrategyBrush),
+    RaiseSizeChanged(String),
+    UpdateSelectStrategyButtons(Vec<Strategy>),
+    StrategiesHaveBeenSaved,
 }
 
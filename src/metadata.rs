@@ -0,0 +1,169 @@
+use std::fmt;
+use std::slice::Iter;
+use serde::{Serialize, Deserialize};
+
+/// The spot a range is played in. Every field is optional so ranges saved
+/// before metadata existed load with none of it set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct RangeMetadata {
+    pub table_size: Option<u8>,
+    pub position: Option<Position>,
+    pub action: Option<ActionContext>,
+    pub stack_depth: Option<u32>,
+    pub game_type: Option<GameType>,
+}
+
+impl RangeMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == RangeMetadata::default()
+    }
+
+    /// A short description of the spot, e.g. `UTG open, 100BB, 6-max, cash`,
+    /// leaving out whatever is not set.
+    pub fn describe(&self) -> String {
+        let spot = match (self.position, self.action) {
+            (Some(position), Some(action)) => Some(format!("{} {}", position, action)),
+            (Some(position), None) => Some(position.to_string()),
+            (None, Some(action)) => Some(action.to_string()),
+            (None, None) => None,
+        };
+        vec![
+            spot,
+            self.stack_depth.map(|stack| format!("{}BB", stack)),
+            self.table_size.map(table_size_name),
+            self.game_type.map(|game_type| game_type.to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>()
+        .join(", ")
+    }
+}
+
+pub const TABLE_SIZES: [u8; 3] = [2, 6, 9];
+
+pub fn table_size_name(players: u8) -> String {
+    match players {
+        2 => String::from("heads-up"),
+        players => format!("{}-max", players),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Position {
+    UnderTheGun,
+    UnderTheGunPlusOne,
+    UnderTheGunPlusTwo,
+    Lojack,
+    Hijack,
+    Cutoff,
+    Button,
+    SmallBlind,
+    BigBlind,
+}
+
+impl Position {
+    pub fn iterator() -> Iter<'static, Position> {
+        use self::Position::*;
+        static POSITIONS: [Position; 9] = [UnderTheGun, UnderTheGunPlusOne,
+        UnderTheGunPlusTwo, Lojack, Hijack, Cutoff, Button, SmallBlind, BigBlind];
+        POSITIONS.iter()
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Position::UnderTheGun => "UTG",
+            Position::UnderTheGunPlusOne => "UTG+1",
+            Position::UnderTheGunPlusTwo => "UTG+2",
+            Position::Lojack => "LJ",
+            Position::Hijack => "HJ",
+            Position::Cutoff => "CO",
+            Position::Button => "BTN",
+            Position::SmallBlind => "SB",
+            Position::BigBlind => "BB",
+        })
+    }
+}
+
+/// What the player is facing when the range is played.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionContext {
+    Open,
+    VsLimp,
+    VsOpen,
+    VsThreeBet,
+    VsFourBet,
+}
+
+impl ActionContext {
+    pub fn iterator() -> Iter<'static, ActionContext> {
+        use self::ActionContext::*;
+        static ACTIONS: [ActionContext; 5] = [Open, VsLimp, VsOpen, VsThreeBet, VsFourBet];
+        ACTIONS.iter()
+    }
+}
+
+impl fmt::Display for ActionContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            ActionContext::Open => "open",
+            ActionContext::VsLimp => "vs limp",
+            ActionContext::VsOpen => "vs open",
+            ActionContext::VsThreeBet => "vs 3-bet",
+            ActionContext::VsFourBet => "vs 4-bet",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameType {
+    Cash,
+    Tournament,
+}
+
+impl GameType {
+    pub fn iterator() -> Iter<'static, GameType> {
+        static GAME_TYPES: [GameType; 2] = [GameType::Cash, GameType::Tournament];
+        GAME_TYPES.iter()
+    }
+}
+
+impl fmt::Display for GameType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            GameType::Cash => "cash",
+            GameType::Tournament => "tournament",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActionContext, GameType, Position, RangeMetadata};
+
+    #[test]
+    fn describes_only_what_is_set() {
+        let mut metadata = RangeMetadata::default();
+        assert_eq!(metadata.describe(), "");
+        metadata.position = Some(Position::UnderTheGun);
+        metadata.action = Some(ActionContext::Open);
+        metadata.stack_depth = Some(100);
+        metadata.table_size = Some(6);
+        assert_eq!(metadata.describe(), "UTG open, 100BB, 6-max");
+        metadata.position = None;
+        metadata.action = Some(ActionContext::VsThreeBet);
+        metadata.table_size = Some(2);
+        metadata.game_type = Some(GameType::Tournament);
+        assert_eq!(metadata.describe(), "vs 3-bet, 100BB, heads-up, tournament");
+    }
+
+    #[test]
+    fn missing_fields_load_as_unset() {
+        let metadata: RangeMetadata = serde_json::from_str(r#"{"stack_depth":40}"#).unwrap();
+        assert_eq!(metadata.stack_depth, Some(40));
+        assert_eq!(metadata.position, None);
+    }
+}
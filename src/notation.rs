@@ -0,0 +1,282 @@
+use std::collections::{HashMap, HashSet};
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use crate::card::Card;
+use crate::hand::{Hand, Suit};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseNotationError(pub String);
+
+/// Parses shorthand range notation such as `22+, A2s-A5s, KTo+, AK:0.5`.
+///
+/// Tokens are separated by commas. A token is a single hand class, a class
+/// followed by `+` (every pair above, or every kicker up to one below the
+/// top card) or two classes joined by `-`. Leaving off `s`/`o` on an unpaired
+/// hand means both. A `:weight` suffix plays the token's hands at that
+/// frequency instead of always.
+pub fn parse(s: &str) -> Result<HashMap<Hand, f32>, ParseNotationError> {
+    let mut weighted = HashMap::new();
+    for token in s.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        let error = || ParseNotationError(token.to_string());
+        let mut parts = token.splitn(2, ':');
+        let hands = parse_token(parts.next().unwrap_or("").trim()).ok_or_else(error)?;
+        let weight = match parts.next() {
+            Some(weight) => weight.trim().parse::<f32>().map_err(|_| error())?,
+            None => 1.0,
+        };
+        if !(weight > 0.0 && weight <= 1.0) {
+            return Err(error());
+        }
+        for hand in hands {
+            weighted.insert(hand, weight);
+        }
+    }
+    Ok(weighted)
+}
+
+fn parse_token(token: &str) -> Option<HashSet<Hand>> {
+    lazy_static! {
+        static ref SINGLE_RE: Regex = Regex::new("^([2-9TJQKA])([2-9TJQKA])([so]?)(\\+?)$").unwrap();
+        static ref DASH_RE: Regex = Regex::new("^([2-9TJQKA])([2-9TJQKA])([so]?)-([2-9TJQKA])([2-9TJQKA])([so]?)$").unwrap();
+    }
+    let mut hands = HashSet::new();
+    if let Some(groups) = SINGLE_RE.captures(token) {
+        let first = rank(&groups[1]);
+        let second = rank(&groups[2]);
+        let suits = suits(first, second, &groups[3])?;
+        let plus = &groups[4] == "+";
+        let (high, low) = (Card::max(first, second), Card::min(first, second));
+        if high == low {
+            let top = if plus { u8::from(Card::Ace) } else { u8::from(high) };
+            for pair in u8::from(high)..=top {
+                hands.insert(pair_of(card(pair)));
+            }
+        } else {
+            let top = if plus { u8::from(high) - 1 } else { u8::from(low) };
+            for kicker in u8::from(low)..=top {
+                for suited in suits.iter() {
+                    hands.insert(Hand { first: high, second: card(kicker), suited: *suited });
+                }
+            }
+        }
+    } else if let Some(groups) = DASH_RE.captures(token) {
+        let (a_first, a_second) = (rank(&groups[1]), rank(&groups[2]));
+        let (b_first, b_second) = (rank(&groups[4]), rank(&groups[5]));
+        if groups[3] != groups[6] {
+            return None;
+        }
+        if a_first == a_second && b_first == b_second {
+            suits(a_first, a_second, &groups[3])?;
+            let (from, to) = (u8::min(a_first.into(), b_first.into()), u8::max(a_first.into(), b_first.into()));
+            for pair in from..=to {
+                hands.insert(pair_of(card(pair)));
+            }
+        } else {
+            let high = Card::max(a_first, a_second);
+            if high != Card::max(b_first, b_second) {
+                return None;
+            }
+            let a_low = Card::min(a_first, a_second);
+            let b_low = Card::min(b_first, b_second);
+            if a_low == high || b_low == high {
+                return None;
+            }
+            let suits = suits(high, a_low, &groups[3])?;
+            let (from, to) = (u8::min(a_low.into(), b_low.into()), u8::max(a_low.into(), b_low.into()));
+            for kicker in from..=to {
+                for suited in suits.iter() {
+                    hands.insert(Hand { first: high, second: card(kicker), suited: *suited });
+                }
+            }
+        }
+    } else {
+        return None;
+    }
+    Some(hands)
+}
+
+/// Formats hands in the shortest canonical notation. Hands are grouped by
+/// weight, most frequent first, and within a weight pairs come first and
+/// then hands by their top card.
+pub fn format(hands: &HashMap<Hand, f32>) -> String {
+    let mut weights = hands.values().copied().filter(|w| *w > 0.0).collect::<Vec<f32>>();
+    weights.sort_by(|a, b| b.partial_cmp(a).unwrap());
+    weights.dedup();
+    let mut tokens = vec![];
+    for weight in weights {
+        let group = hands
+            .iter()
+            .filter(|(_, w)| **w == weight)
+            .map(|(hand, _)| normalize(hand))
+            .collect::<HashSet<Hand>>();
+        for token in format_group(&group) {
+            if weight < 1.0 {
+                tokens.push(format!("{}:{}", token, weight));
+            } else {
+                tokens.push(token);
+            }
+        }
+    }
+    tokens.join(", ")
+}
+
+fn format_group(hands: &HashSet<Hand>) -> Vec<String> {
+    let mut tokens = vec![];
+
+    let pairs = Card::iterator()
+        .map(|c| hands.contains(&pair_of(*c)))
+        .collect::<Vec<bool>>();
+    for (from, to) in runs(&pairs).into_iter().rev() {
+        tokens.push(run_token(&pair_of(card_at(from)), &pair_of(card_at(to)), to == 12));
+    }
+
+    for high in (1..13).rev() {
+        let kickers = |suited: Suit| {
+            (0..high)
+                .map(|k| hands.contains(&Hand { first: card_at(high), second: card_at(k), suited }))
+                .collect::<Vec<bool>>()
+        };
+        let suited_runs = runs(&kickers(Suit::Suited));
+        let off_runs = runs(&kickers(Suit::Off));
+        if suited_runs == off_runs {
+            for (from, to) in suited_runs.into_iter().rev() {
+                let low = format!("{}{}", card_at(high), card_at(from));
+                let top = format!("{}{}", card_at(high), card_at(to));
+                tokens.push(if to + 1 == high && from != to {
+                    format!("{}+", low)
+                } else if from == to {
+                    low
+                } else {
+                    format!("{}-{}", top, low)
+                });
+            }
+        } else {
+            for (suited, runs) in [(Suit::Suited, &suited_runs), (Suit::Off, &off_runs)].iter() {
+                for (from, to) in runs.iter().rev() {
+                    let low = Hand { first: card_at(high), second: card_at(*from), suited: *suited };
+                    let top = Hand { first: card_at(high), second: card_at(*to), suited: *suited };
+                    tokens.push(run_token(&low, &top, to + 1 == high));
+                }
+            }
+        }
+    }
+
+    tokens
+}
+
+fn run_token(low: &Hand, top: &Hand, open_ended: bool) -> String {
+    if low == top {
+        low.to_string()
+    } else if open_ended {
+        format!("{}+", low)
+    } else {
+        format!("{}-{}", top, low)
+    }
+}
+
+/// Maximal runs of consecutive `true` values as inclusive index pairs.
+fn runs(flags: &[bool]) -> Vec<(u8, u8)> {
+    let mut runs = vec![];
+    let mut start = None;
+    for (i, flag) in flags.iter().chain(std::iter::once(&false)).enumerate() {
+        match (start, flag) {
+            (None, true) => start = Some(i as u8),
+            (Some(s), false) => {
+                runs.push((s, i as u8 - 1));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+fn normalize(hand: &Hand) -> Hand {
+    Hand {
+        first: Card::max(hand.first, hand.second),
+        second: Card::min(hand.first, hand.second),
+        suited: if hand.first == hand.second { Suit::Off } else { hand.suited },
+    }
+}
+
+fn suits(first: Card, second: Card, suffix: &str) -> Option<Vec<Suit>> {
+    match (first == second, suffix) {
+        (true, "") => Some(vec![Suit::Off]),
+        (true, _) => None,
+        (false, "s") => Some(vec![Suit::Suited]),
+        (false, "o") => Some(vec![Suit::Off]),
+        (false, _) => Some(vec![Suit::Suited, Suit::Off]),
+    }
+}
+
+fn pair_of(card: Card) -> Hand {
+    Hand { first: card, second: card, suited: Suit::Off }
+}
+
+fn rank(s: &str) -> Card {
+    s.parse::<Card>().unwrap()
+}
+
+fn card(value: u8) -> Card {
+    card_at(value - 2)
+}
+
+fn card_at(index: u8) -> Card {
+    *Card::iterator().nth(index as usize).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use quickcheck_macros::quickcheck;
+    use crate::hand::Hand;
+    use super::{format, normalize, parse};
+
+    fn hands(s: &str) -> HashMap<Hand, f32> {
+        s.split_whitespace().map(|h| (h.parse::<Hand>().unwrap(), 1.0)).collect()
+    }
+
+    #[quickcheck]
+    fn format_parse_hands(hands: Vec<(Hand, u8)>) -> bool {
+        let hands = hands
+            .iter()
+            .map(|(hand, weight)| (normalize(hand), (*weight % 100 + 1) as f32 / 100.0))
+            .collect::<HashMap<Hand, f32>>();
+        parse(&format(&hands)).unwrap() == hands
+    }
+
+    #[test]
+    fn parse_shorthand() {
+        assert_eq!(parse("22+").unwrap().len(), 13);
+        assert_eq!(parse("A2s-A5s").unwrap(), hands("A2s A3s A4s A5s"));
+        assert_eq!(parse("A5s-A2s").unwrap(), hands("A2s A3s A4s A5s"));
+        assert_eq!(parse("KTo+").unwrap(), hands("KTo KJo KQo"));
+        assert_eq!(parse("QQ-TT, AK").unwrap(), hands("QQ JJ TT AKs AKo"));
+        assert_eq!(parse("KA").unwrap(), hands("AKs AKo"));
+        assert_eq!(parse(" ").unwrap(), HashMap::new());
+        let mut weighted = hands("AA");
+        weighted.insert("A5s".parse::<Hand>().unwrap(), 0.5);
+        assert_eq!(parse("A5s:0.5, AA").unwrap(), weighted);
+        assert!(parse("AKs:0").is_err());
+        assert!(parse("AKs:1.5").is_err());
+        assert!(parse("AKx").is_err());
+        assert!(parse("AKs-QJs").is_err());
+        assert!(parse("AKs-ATo").is_err());
+        assert!(parse("AAs").is_err());
+        assert!(parse("AAo").is_err());
+        assert!(parse("AAs-KKs").is_err());
+        assert!(parse("AAo-KKo").is_err());
+        assert!(parse("AA-KKs").is_err());
+    }
+
+    #[test]
+    fn format_is_compact() {
+        assert_eq!(format(&parse("TT+, AQs+, AKo").unwrap()), "TT+, AQs+, AKo");
+        assert_eq!(format(&hands("AA")), "AA");
+        assert_eq!(format(&hands("77 66 55")), "77-55");
+        assert_eq!(format(&hands("AKs AKo AQs AQo")), "AQ+");
+        assert_eq!(format(&hands("A5s A4s A3s")), "A5s-A3s");
+        assert_eq!(format(&parse("QQ+, A5s:0.5, A4s:0.5, 76s:0.25").unwrap()), "QQ+, A5s-A4s:0.5, 76s:0.25");
+    }
+}
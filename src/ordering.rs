@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::slice::Iter;
+use lazy_static::lazy_static;
+
+use crate::equity;
+use crate::hand::Hand;
+use crate::notation;
+use crate::range::Range;
+
+/// Ranks starting hands so percentage ranges can take the strongest ones.
+pub trait HandOrdering {
+    /// How strong `hand` is. Only the order of the values matters.
+    fn strength(&self, hand: &Hand) -> f32;
+
+    /// Every starting hand, strongest first.
+    fn order(&self) -> Vec<Hand> {
+        let mut hands = Hand::all();
+        hands.sort_by(|a, b| self.strength(b).partial_cmp(&self.strength(a)).unwrap());
+        hands
+    }
+}
+
+/// Bill Chen's points formula.
+pub struct Chen;
+
+impl HandOrdering for Chen {
+    fn strength(&self, hand: &Hand) -> f32 {
+        hand.chen_value()
+    }
+}
+
+/// David Sklansky and Mason Malmuth's eight hand groups. Hands outside the
+/// groups come last and Chen points break ties within a group.
+pub struct SklanskyMalmuth;
+
+impl SklanskyMalmuth {
+    /// The group of `hand`, from 1 for the strongest to 9 for unlisted hands.
+    pub fn group(hand: &Hand) -> u8 {
+        lazy_static! {
+            static ref GROUPS: HashMap<Hand, u8> = {
+                let groups = [
+                    "AA, KK, QQ, JJ, AKs",
+                    "TT, AQs, AJs, KQs, AKo",
+                    "99, JTs, QJs, KJs, ATs, AQo",
+                    "T9s, KQo, 88, QTs, 98s, J9s, AJo, KTs",
+                    "77, 87s, Q9s, T8s, KJo, QJo, JTo, 76s, 97s, A9s-A2s, 65s",
+                    "66, ATo, 55, 86s, KTo, QTo, 54s, K9s, J8s, 75s",
+                    "44, J9o, 64s, T9o, 53s, 33, 98o, 43s, 22, K8s-K2s, T7s, Q8s",
+                    "87o, A9o, Q9o, 76o, 42s, 32s, 96s, 85s, J8o, J7s, 65o, 54o, 74s, K9o, T8o",
+                ];
+                let mut hands = HashMap::new();
+                for (group, notation) in groups.iter().enumerate() {
+                    for hand in notation::parse(notation).unwrap().keys() {
+                        hands.insert(*hand, group as u8 + 1);
+                    }
+                }
+                hands
+            };
+        }
+        GROUPS.get(hand).copied().unwrap_or(9)
+    }
+}
+
+impl HandOrdering for SklanskyMalmuth {
+    fn strength(&self, hand: &Hand) -> f32 {
+        (9 - Self::group(hand)) as f32 + hand.chen_value() / 100.0
+    }
+}
+
+/// Preflop all-in equity against a villain range, or against any two cards.
+pub struct EquityVsRange {
+    equities: HashMap<Hand, f32>,
+}
+
+impl EquityVsRange {
+    pub fn new(villain: &Range, settings: &equity::Settings) -> Self {
+        let equities = equity::range_vs_range(&Range::from_percent(100), villain, &[], settings)
+            .per_hand
+            .iter()
+            .map(|(hand, equity)| (*hand, equity.equity_percent()))
+            .collect();
+        Self { equities }
+    }
+
+    pub fn random(settings: &equity::Settings) -> Self {
+        Self::new(&Range::from_percent(100), settings)
+    }
+}
+
+impl HandOrdering for EquityVsRange {
+    fn strength(&self, hand: &Hand) -> f32 {
+        self.equities.get(hand).copied().unwrap_or(0.0)
+    }
+}
+
+/// The orderings the range editor offers.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OrderingKind {
+    #[default]
+    Chen,
+    SklanskyMalmuth,
+    EquityVsRandom,
+    EquityVsRange,
+}
+
+impl OrderingKind {
+    pub fn iterator() -> Iter<'static, OrderingKind> {
+        static KINDS: [OrderingKind; 4] = [
+            OrderingKind::Chen,
+            OrderingKind::SklanskyMalmuth,
+            OrderingKind::EquityVsRandom,
+            OrderingKind::EquityVsRange,
+        ];
+        KINDS.iter()
+    }
+}
+
+impl fmt::Display for OrderingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            OrderingKind::Chen => "Chen",
+            OrderingKind::SklanskyMalmuth => "Sklansky-Malmuth",
+            OrderingKind::EquityVsRandom => "equity vs random",
+            OrderingKind::EquityVsRange => "equity vs range",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::equity::Settings;
+    use crate::hand::Hand;
+    use crate::range::Range;
+    use super::{Chen, EquityVsRange, HandOrdering, SklanskyMalmuth};
+
+    fn hand(hand: &str) -> Hand {
+        hand.parse().unwrap()
+    }
+
+    #[test]
+    fn orderings_cover_every_hand() {
+        let settings = Settings { exact_limit: 0, trials: 4_000, seed: 0 };
+        let orderings: Vec<Box<dyn HandOrdering>> =
+            vec![Box::new(Chen), Box::new(SklanskyMalmuth), Box::new(EquityVsRange::random(&settings))];
+        for ordering in orderings {
+            let order = ordering.order();
+            assert_eq!(order.len(), 169);
+            assert_eq!(order[0], hand("AA"));
+        }
+    }
+
+    #[test]
+    fn groups_rank_hands() {
+        assert_eq!(SklanskyMalmuth::group(&hand("AKs")), 1);
+        assert_eq!(SklanskyMalmuth::group(&hand("A5s")), 5);
+        assert_eq!(SklanskyMalmuth::group(&hand("K2s")), 7);
+        assert_eq!(SklanskyMalmuth::group(&hand("72o")), 9);
+        let order = SklanskyMalmuth.order();
+        let position = |h| order.iter().position(|o| *o == hand(h)).unwrap();
+        assert!(position("AKo") < position("99"));
+        assert!(position("T8o") < position("A8o"));
+    }
+
+    #[test]
+    fn equity_orderings_follow_the_villain() {
+        let settings = Settings { exact_limit: 0, trials: 4_000, seed: 0 };
+        let random = EquityVsRange::random(&settings);
+        assert!(random.strength(&hand("KQo")) > random.strength(&hand("76s")));
+        let aces = Range {
+            hands: crate::notation::parse("AA").unwrap(),
+            ..Range::new()
+        };
+        let vs_aces = EquityVsRange::new(&aces, &settings);
+        assert!(vs_aces.strength(&hand("76s")) > vs_aces.strength(&hand("KQo")));
+    }
+}
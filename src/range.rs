@@ -1,69 +1,477 @@
-use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use serde::{Serialize, Deserialize};
-use itertools::Itertools;
-use crate::hand::{Hand, Suit};
-use crate::card::Card;
+use crate::card::SpecificCard;
+use crate::hand::Hand;
+use crate::metadata::RangeMetadata;
+use crate::notation;
+use crate::ordering::{Chen, HandOrdering};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Number of distinct two card starting hands.
+pub const TOTAL_COMBOS: f32 = 1326.0;
+
+/// A named set of hand classes, each played with a frequency in `(0, 1]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Range {
+    /// Stays the same through renames and edits so questions about the
+    /// range keep their history. Empty for ranges saved before IDs existed.
+    #[serde(default)]
+    pub id: String,
     pub name: String,
-    pub hands: HashSet<Hand>
+    #[serde(with = "weighted_hands")]
+    pub hands: HashMap<Hand, f32>,
+    #[serde(default)]
+    pub metadata: RangeMetadata,
+    /// Where the range lives in the library, e.g. `6-max/UTG`. Empty for the
+    /// top level.
+    #[serde(default)]
+    pub folder: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A set operation combining the active range with another one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RangeOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+/// How two ranges overlap, split into the frequency only the first plays,
+/// the frequency both play and the frequency only the second plays.
+#[derive(Debug, Clone)]
+pub struct RangeComparison {
+    pub only_first: Range,
+    pub both: Range,
+    pub only_second: Range,
 }
 
 impl Range {
     pub fn new() -> Self {
         Self::default()
     }
+    /// The strongest hands by Chen value making up `percent` of all
+    /// starting combos.
     pub fn from_percent(percent: u8) -> Self {
-        let first_cards = Card::iterator();
-        let second_cards = Card::iterator();
-        let suits = [Suit::Suited, Suit::Off].iter();
-
-
-        let mut all_cards = suits
-            .cartesian_product(first_cards.cartesian_product(second_cards))
-            .into_iter()
-            .map(|(suited, (first, second))| {
-                Hand { first: *first, second: *second, suited: *suited }
-            })
-            .filter(|hand| {
-                !(hand.first == hand.second && hand.suited == Suit::Suited)        
-            })
-            .collect::<Vec<Hand>>();
-        
-        all_cards.sort_unstable_by(|a, b| {
-            a.chen_value().partial_cmp(&b.chen_value()).unwrap()
-        });
+        Self::from_order(percent, &Chen.order())
+    }
+
+    /// The first hands of `order` making up `percent` of all starting combos.
+    pub fn from_order(percent: u8, order: &[Hand]) -> Self {
+        let target = TOTAL_COMBOS * percent.min(100) as f32 / 100.0;
+        let mut combos = 0.0;
+        let mut hands = HashMap::<Hand, f32>::new();
+        for hand in order.iter().copied() {
+            combos += hand.combo_count() as f32;
+            if combos > target {
+                break;
+            }
+            hands.insert(hand, 1.0);
+        }
 
         Range {
             name: format!("Top {} percent", percent),
-            hands: all_cards
-                .iter()
-                .rev()
-                .take(all_cards.len() * percent as usize / 100_usize)
-                .map(|x| *x)
-                .collect::<HashSet<Hand>>()
+            hands,
+            ..Range::default()
         }
     }
 
+    /// How questions refer to the range: its spot when metadata has been
+    /// filled in, otherwise its name.
+    pub fn label(&self) -> String {
+        if self.metadata.is_empty() {
+            self.name.to_string()
+        } else {
+            self.metadata.describe()
+        }
+    }
+
+    /// The folder split into its nested parts, ignoring empty ones.
+    pub fn folder_path(&self) -> Vec<&str> {
+        self.folder
+            .split('/')
+            .map(|part| part.trim())
+            .filter(|part| !part.is_empty())
+            .collect()
+    }
+
+    /// Sets the tags from a comma separated list.
+    pub fn set_tags(&mut self, tags: &str) {
+        self.tags = tags
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+    }
+
+    /// Whether every word of `query` appears in the name, folder, tags or
+    /// spot of the range, ignoring case.
+    pub fn matches(&self, query: &str) -> bool {
+        let haystack = format!(
+            "{} {} {} {}",
+            self.name,
+            self.folder,
+            self.tags.join(" "),
+            self.metadata.describe()
+        )
+        .to_lowercase();
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| haystack.contains(word))
+    }
+
     pub fn toggle(&mut self, hand: Hand) {
-        if self.hands.contains(&hand) {
+        self.toggle_weighted(hand, 1.0);
+    }
+
+    /// Sets `hand` to `weight`, or removes it if it is already played at
+    /// that weight.
+    pub fn toggle_weighted(&mut self, hand: Hand, weight: f32) {
+        if self.weight(&hand) == weight {
             self.hands.remove(&hand);
         } else {
-            self.hands.insert(hand);
+            self.set_weight(hand, weight);
         }
     }
 
+    pub fn set_weight(&mut self, hand: Hand, weight: f32) {
+        if weight > 0.0 {
+            self.hands.insert(hand, weight.min(1.0));
+        } else {
+            self.hands.remove(&hand);
+        }
+    }
+
+    /// How often `hand` is played, `0.0` if it is not in the range.
+    pub fn weight(&self, hand: &Hand) -> f32 {
+        self.hands.get(hand).copied().unwrap_or(0.0)
+    }
+
     pub fn contains(&self, hand: &Hand) -> bool {
-        self.hands.contains(hand)
+        self.weight(hand) > 0.0
+    }
+
+    /// Number of combos in the range, counting mixed hands by their weight.
+    pub fn combo_count(&self) -> f32 {
+        self.hands
+            .iter()
+            .map(|(hand, weight)| hand.combo_count() as f32 * weight)
+            .sum()
+    }
+
+    /// Share of all starting combos in the range, as a percentage.
+    pub fn percent(&self) -> f32 {
+        self.combo_count() / TOTAL_COMBOS * 100.0
+    }
+
+    /// Weighted combos of each hand still possible once `dead` cards, such
+    /// as hole cards or a board, are removed from the deck. Fully blocked
+    /// hands are left out.
+    pub fn live_combo_counts(&self, dead: &[SpecificCard]) -> HashMap<Hand, f32> {
+        self.hands
+            .iter()
+            .map(|(hand, weight)| (*hand, hand.live_combos(dead).len() as f32 * weight))
+            .filter(|(_, count)| *count > 0.0)
+            .collect()
+    }
+
+    pub fn live_combo_count(&self, dead: &[SpecificCard]) -> f32 {
+        self.live_combo_counts(dead).values().sum()
+    }
+
+    /// Hands in either range, each at the higher of its two weights.
+    pub fn union(&self, other: &Range) -> Range {
+        let mut range = self.clone();
+        for (hand, weight) in other.hands.iter() {
+            range.set_weight(*hand, f32::max(*weight, self.weight(hand)));
+        }
+        range
+    }
+
+    /// Hands in both ranges, each at the lower of its two weights.
+    pub fn intersection(&self, other: &Range) -> Range {
+        let mut range = self.clone();
+        for (hand, weight) in self.hands.iter() {
+            range.set_weight(*hand, f32::min(*weight, other.weight(hand)));
+        }
+        range
+    }
+
+    /// This range with `other` taken out, e.g. a calling range minus a three
+    /// bet range. Weights are subtracted.
+    pub fn difference(&self, other: &Range) -> Range {
+        let mut range = self.clone();
+        for (hand, weight) in self.hands.iter() {
+            range.set_weight(*hand, *weight - other.weight(hand));
+        }
+        range
+    }
+
+    /// Every hand at the frequency this range does not play it.
+    pub fn complement(&self) -> Range {
+        let mut range = self.clone();
+        for hand in Hand::all() {
+            range.set_weight(hand, 1.0 - self.weight(&hand));
+        }
+        range
+    }
+
+    pub fn combine(&self, other: &Range, operation: RangeOperation) -> Range {
+        match operation {
+            RangeOperation::Union => self.union(other),
+            RangeOperation::Intersection => self.intersection(other),
+            RangeOperation::Difference => self.difference(other),
+        }
+    }
+
+    pub fn compare(&self, other: &Range) -> RangeComparison {
+        RangeComparison {
+            only_first: self.difference(other),
+            both: self.intersection(other),
+            only_second: other.difference(self),
+        }
     }
+
+    /// The hands in compact shorthand, e.g. `TT+, AQs+, AKo`.
+    pub fn notation(&self) -> String {
+        notation::format(&self.hands)
+    }
+
+    /// A hash of the hands and their weights, to tell when they have changed.
+    pub fn hands_hash(&self) -> u64 {
+        self.hands.iter().fold(0, |sum, (hand, weight)| {
+            let mut hasher = DefaultHasher::new();
+            (hand, weight.to_bits()).hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        })
+    }
+}
+
+/// The range in `ranges` with ID `id`.
+pub fn find<'a>(ranges: &'a [Range], id: &str) -> Option<&'a Range> {
+    ranges.iter().find(|range| range.id == id)
+}
+
+/// A new random ID for a range or strategy.
+pub fn new_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
 }
 
 impl Default for Range {
     fn default() -> Range {
         Range {
+            id: new_id(),
             name: String::from("untitled range"),
-            hands: HashSet::<Hand>::new(),
+            hands: HashMap::<Hand, f32>::new(),
+            metadata: RangeMetadata::default(),
+            folder: String::new(),
+            tags: vec![],
         }
     }
 }
+
+/// Stores hands as a `{"AKs": 0.5}` map. Ranges saved before weights existed
+/// hold a plain list of hands, which load at full weight.
+mod weighted_hands {
+    use std::collections::HashMap;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use crate::hand::Hand;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StoredHands {
+        Weighted(HashMap<String, f32>),
+        Unweighted(Vec<Hand>),
+    }
+
+    pub fn serialize<S>(hands: &HashMap<Hand, f32>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(hands.iter().map(|(hand, weight)| (hand.to_string(), weight)))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<Hand, f32>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match StoredHands::deserialize(deserializer)? {
+            StoredHands::Weighted(hands) => hands
+                .into_iter()
+                .map(|(hand, weight)| {
+                    hand.parse::<Hand>()
+                        .map(|hand| (hand, weight))
+                        .map_err(|_| serde::de::Error::custom(format!("invalid hand {}", hand)))
+                })
+                .collect(),
+            StoredHands::Unweighted(hands) => Ok(hands.into_iter().map(|hand| (hand, 1.0)).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+    use crate::hand::Hand;
+    use super::{Range, RangeOperation};
+
+    impl Arbitrary for Range {
+        fn arbitrary<G: Gen>(g: &mut G) -> Range {
+            let mut range = Range::new();
+            for hand in Hand::all() {
+                match g.next_u32() % 4 {
+                    0 => range.set_weight(hand, 1.0),
+                    1 => range.set_weight(hand, (g.next_u32() % 20 + 1) as f32 / 20.0),
+                    _ => {}
+                }
+            }
+            range
+        }
+    }
+
+    fn range(notation: &str) -> Range {
+        Range {
+            name: String::from(notation),
+            hands: crate::notation::parse(notation).unwrap(),
+            ..Range::new()
+        }
+    }
+
+    #[quickcheck]
+    fn difference_and_intersection_split_a_range(a: Range, b: Range) -> bool {
+        let split = a.difference(&b).combo_count() + a.intersection(&b).combo_count();
+        (split - a.combo_count()).abs() < 0.01
+    }
+
+    #[quickcheck]
+    fn complement_partitions_all_combos(a: Range) -> bool {
+        (a.combo_count() + a.complement().combo_count() - 1326.0).abs() < 0.01
+    }
+
+    #[quickcheck]
+    fn comparison_buckets_cover_both_ranges(a: Range, b: Range) -> bool {
+        let comparison = a.compare(&b);
+        let first = comparison.only_first.combo_count() + comparison.both.combo_count();
+        let second = comparison.only_second.combo_count() + comparison.both.combo_count();
+        (first - a.combo_count()).abs() < 0.01 && (second - b.combo_count()).abs() < 0.01
+    }
+
+    #[test]
+    fn set_operations_on_weights() {
+        let calls = range("TT-77, AQs, KQs:0.5");
+        let three_bets = range("TT+, AQs:0.25, KQs");
+        assert_eq!(calls.union(&three_bets).notation(), "77+, AQs, KQs");
+        assert_eq!(calls.intersection(&three_bets).notation(), "TT, KQs:0.5, AQs:0.25");
+        assert_eq!(calls.difference(&three_bets).notation(), "99-77, AQs:0.75");
+        assert_eq!(range("22+").complement().hands.len(), 156);
+    }
+
+    #[test]
+    fn set_operations_find_the_other_range_after_a_delete() {
+        let mut ranges = vec![range("AA"), range("KK"), range("QQ")];
+        let other = ranges[2].id.clone();
+        ranges.remove(0);
+        let combined = ranges[0].combine(super::find(&ranges, &other).unwrap(), RangeOperation::Union);
+        assert_eq!(combined.notation(), "KK-QQ");
+        ranges.retain(|range| range.id != other);
+        assert!(super::find(&ranges, &other).is_none());
+    }
+
+    #[test]
+    fn hands_hash_follows_hands() {
+        let calls = range("TT-77, AQs, KQs:0.5");
+        assert_eq!(calls.hands_hash(), range("KQs:0.5, AQs, 77-TT").hands_hash());
+        assert_ne!(calls.hands_hash(), range("TT-77, AQs, KQs:0.25").hands_hash());
+        assert_ne!(calls.hands_hash(), range("TT-77, AQs").hands_hash());
+    }
+
+    #[test]
+    fn loads_unweighted_ranges() {
+        let json = r#"{"name":"old","hands":[{"first":"Ace","second":"King","suited":"Suited"}]}"#;
+        let range: Range = serde_json::from_str(json).unwrap();
+        assert_eq!(range.weight(&"AKs".parse::<Hand>().unwrap()), 1.0);
+        assert_eq!(range.hands.len(), 1);
+        assert!(range.metadata.is_empty());
+        assert_eq!(range.label(), "old");
+        assert!(range.folder_path().is_empty());
+        assert!(range.tags.is_empty());
+        assert!(range.id.is_empty());
+        assert_ne!(Range::new().id, Range::new().id);
+    }
+
+    #[test]
+    fn searches_name_folder_and_tags() {
+        let mut range = range("TT+, AK");
+        range.name = String::from("UTG open");
+        range.folder = String::from(" 6-max / Cash /");
+        range.set_tags("solver, , Value ");
+        assert_eq!(range.folder_path(), vec!["6-max", "Cash"]);
+        assert_eq!(range.tags, vec!["solver", "Value"]);
+        assert!(range.matches(""));
+        assert!(range.matches("utg VALUE"));
+        assert!(range.matches("6-max solver"));
+        assert!(!range.matches("utg btn"));
+    }
+
+    #[test]
+    fn weighted_ranges_round_trip() {
+        let mut range = Range::new();
+        range.set_weight("A5s".parse::<Hand>().unwrap(), 0.5);
+        range.toggle("QQ".parse::<Hand>().unwrap());
+        let json = serde_json::to_string(&range).unwrap();
+        let loaded: Range = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.hands, range.hands);
+    }
+
+    #[test]
+    fn counts_weighted_combos() {
+        let mut range = Range::new();
+        range.toggle("AA".parse::<Hand>().unwrap());
+        range.toggle("AKs".parse::<Hand>().unwrap());
+        range.set_weight("AKo".parse::<Hand>().unwrap(), 0.5);
+        assert_eq!(range.combo_count(), 16.0);
+        assert_eq!(Range::from_percent(100).combo_count(), 1326.0);
+    }
+
+    #[test]
+    fn blockers_remove_combos() {
+        let range = Range {
+            name: String::from("value"),
+            hands: crate::notation::parse("QQ+, AK").unwrap(),
+            ..Range::new()
+        };
+        let dead = ["As".parse().unwrap(), "Kd".parse().unwrap()];
+        let counts = range.live_combo_counts(&dead);
+        assert_eq!(counts[&"QQ".parse::<Hand>().unwrap()], 6.0);
+        assert_eq!(counts[&"KK".parse::<Hand>().unwrap()], 3.0);
+        assert_eq!(counts[&"AKs".parse::<Hand>().unwrap()], 2.0);
+        assert_eq!(counts[&"AKo".parse::<Hand>().unwrap()], 7.0);
+        assert_eq!(range.live_combo_count(&dead), 21.0);
+        assert_eq!(range.live_combo_count(&[]), range.combo_count());
+    }
+
+    #[test]
+    fn percent_ranges_count_combos() {
+        for percent in [5, 10, 25, 50].iter() {
+            let range = Range::from_percent(*percent);
+            assert!(range.percent() <= *percent as f32);
+            assert!(range.percent() > *percent as f32 - 2.0);
+        }
+        assert!(Range::from_percent(0).hands.is_empty());
+    }
+
+    #[test]
+    fn toggle_weighted_replaces_then_removes() {
+        let hand = "A5s".parse::<Hand>().unwrap();
+        let mut range = Range::new();
+        range.toggle(hand);
+        range.toggle_weighted(hand, 0.5);
+        assert_eq!(range.weight(&hand), 0.5);
+        range.toggle_weighted(hand, 0.5);
+        assert!(!range.contains(&hand));
+    }
+}
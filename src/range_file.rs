@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::slice::Iter;
+
+use crate::card::Card;
+use crate::combo::Combo;
+use crate::hand::{Hand, Suit};
+use crate::notation;
+use crate::range::Range;
+
+/// Text formats ranges are shared in with other tools.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RangeFormat {
+    /// Shorthand notation such as `TT+, AQs+, AKo:0.5`.
+    #[default]
+    Notation,
+    /// Every hand with its frequency, e.g. `AA:1,AKs:0.5`, as solvers and
+    /// equity calculators export them.
+    Weighted,
+    /// A 13x13 CSV grid of frequencies laid out like the hand matrix.
+    Grid,
+}
+
+impl RangeFormat {
+    pub fn iterator() -> Iter<'static, RangeFormat> {
+        static FORMATS: [RangeFormat; 3] = [RangeFormat::Notation, RangeFormat::Weighted, RangeFormat::Grid];
+        FORMATS.iter()
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RangeFormat::Notation | RangeFormat::Weighted => "txt",
+            RangeFormat::Grid => "csv",
+        }
+    }
+}
+
+impl fmt::Display for RangeFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            RangeFormat::Notation => "notation",
+            RangeFormat::Weighted => "hand:frequency",
+            RangeFormat::Grid => "CSV grid",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseRangeFileError(pub String);
+
+impl fmt::Display for ParseRangeFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not read {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseRangeFileError {}
+
+pub fn format(range: &Range, format: RangeFormat) -> String {
+    match format {
+        RangeFormat::Notation => range.notation(),
+        RangeFormat::Weighted => Hand::all()
+            .iter()
+            .filter(|hand| range.contains(hand))
+            .map(|hand| format!("{}:{}", hand, range.weight(hand)))
+            .collect::<Vec<String>>()
+            .join(","),
+        RangeFormat::Grid => {
+            let ranks = ranks();
+            let mut lines = vec![format!(
+                ",{}",
+                ranks.iter().map(|rank| rank.to_string()).collect::<Vec<String>>().join(",")
+            )];
+            for (row, rank) in ranks.iter().enumerate() {
+                let weights = (0..13)
+                    .map(|column| range.weight(&grid_hand(row, column)).to_string())
+                    .collect::<Vec<String>>();
+                lines.push(format!("{},{}", rank, weights.join(",")));
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+/// Reads a range in any of the supported formats, telling them apart by
+/// their contents.
+pub fn parse(text: &str) -> Result<HashMap<Hand, f32>, ParseRangeFileError> {
+    let rows = text
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>();
+    if (rows.len() == 13 || rows.len() == 14) && rows.iter().all(|row| row.split(',').count() >= 13) {
+        parse_grid(&rows)
+    } else {
+        parse_list(&rows.join(","))
+    }
+}
+
+/// Notation or a `hand:frequency` list. Specific combos such as `AhKh:0.5`
+/// count towards their hand class in proportion to its combos.
+fn parse_list(text: &str) -> Result<HashMap<Hand, f32>, ParseRangeFileError> {
+    let mut hands = HashMap::<Hand, f32>::new();
+    let mut classes = vec![];
+    for token in text.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        let error = || ParseRangeFileError(token.to_string());
+        let mut parts = token.splitn(2, ':');
+        let cards = parts.next().unwrap_or("").trim();
+        let weight = match parts.next() {
+            Some(weight) => weight.trim().parse::<f32>().map_err(|_| error())?,
+            None => 1.0,
+        };
+        if weight == 0.0 {
+            continue;
+        }
+        match cards.parse::<Combo>() {
+            Ok(combo) => {
+                let hand = Hand::from(combo);
+                *hands.entry(hand).or_insert(0.0) += weight / hand.combo_count() as f32;
+            }
+            Err(_) => classes.push(token),
+        }
+    }
+    let parsed = notation::parse(&classes.join(","))
+        .map_err(|notation::ParseNotationError(token)| ParseRangeFileError(token))?;
+    for (hand, weight) in parsed {
+        hands.insert(hand, weight);
+    }
+    hands.retain(|_, weight| *weight > 0.0);
+    for weight in hands.values_mut() {
+        *weight = weight.min(1.0);
+    }
+    Ok(hands)
+}
+
+/// A grid of frequencies, skipping a header row and label column if there
+/// are any. Empty cells are never played and grids holding values above one
+/// are read as percentages.
+fn parse_grid(rows: &[&str]) -> Result<HashMap<Hand, f32>, ParseRangeFileError> {
+    let rows = if rows.len() == 14 { &rows[1..] } else { rows };
+    let mut cells = vec![];
+    for row in rows {
+        let error = || ParseRangeFileError(row.to_string());
+        let mut values = row.split(',').map(|cell| cell.trim()).collect::<Vec<&str>>();
+        if values.len() == 14 {
+            values.remove(0);
+        }
+        if values.len() != 13 {
+            return Err(error());
+        }
+        let values = values
+            .iter()
+            .map(|cell| match cell.trim_end_matches('%') {
+                "" => Ok(0.0),
+                cell => cell.parse::<f32>().map_err(|_| error()),
+            })
+            .collect::<Result<Vec<f32>, ParseRangeFileError>>()?;
+        cells.push(values);
+    }
+    let scale = if cells.iter().flatten().any(|weight| *weight > 1.0) { 100.0 } else { 1.0 };
+    let mut hands = HashMap::new();
+    for (row, values) in cells.iter().enumerate() {
+        for (column, weight) in values.iter().enumerate() {
+            let weight = (weight / scale).min(1.0);
+            if weight > 0.0 {
+                hands.insert(grid_hand(row, column), weight);
+            }
+        }
+    }
+    Ok(hands)
+}
+
+/// Ranks from ace down, the order of the grid's rows and columns.
+fn ranks() -> Vec<Card> {
+    Card::iterator().rev().copied().collect()
+}
+
+/// The hand in a grid cell: suited hands above the diagonal and offsuit
+/// hands below it.
+pub fn grid_hand(row: usize, column: usize) -> Hand {
+    let ranks = ranks();
+    let (high, low) = (ranks[row.min(column)], ranks[row.max(column)]);
+    Hand {
+        first: high,
+        second: low,
+        suited: if column > row { Suit::Suited } else { Suit::Off },
+    }
+}
+
+/// Where each range goes when a library is exported, mirroring its folders.
+/// Ranges sharing a name in the same folder are numbered.
+pub fn library_paths(ranges: &[Range], format: RangeFormat) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = vec![];
+    for range in ranges {
+        let folder = range.folder_path().iter().map(|folder| safe_name(folder)).collect::<PathBuf>();
+        let mut path = folder.join(file_name(range, format));
+        let mut copy = 1;
+        while paths.contains(&path) {
+            copy += 1;
+            path = folder.join(format!("{} {}.{}", safe_name(&range.name), copy, format.extension()));
+        }
+        paths.push(path);
+    }
+    paths
+}
+
+/// The file a range is exported to, named after the range.
+pub fn file_name(range: &Range, format: RangeFormat) -> String {
+    format!("{}.{}", safe_name(&range.name), format.extension())
+}
+
+/// `name` with anything that is not safe in a file name replaced.
+pub fn safe_name(name: &str) -> String {
+    let name = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || " -_+.()".contains(c) { c } else { '_' })
+        .collect::<String>();
+    match name.trim_start_matches('.') {
+        "" => String::from("range"),
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use quickcheck_macros::quickcheck;
+    use crate::hand::Hand;
+    use crate::range::Range;
+    use super::{RangeFormat, ParseRangeFileError};
+
+    #[quickcheck]
+    fn every_format_round_trips(range: Range) -> bool {
+        super::RangeFormat::iterator().all(|format| {
+            super::parse(&super::format(&range, *format)).unwrap() == range.hands
+        })
+    }
+
+    #[test]
+    fn reads_solver_lists() {
+        let hands = super::parse("AA:1.0,AKs:0.5,\nKQo:0,AhQh:1,AsQs:0.5").unwrap();
+        assert_eq!(hands[&"AA".parse::<Hand>().unwrap()], 1.0);
+        assert_eq!(hands[&"AKs".parse::<Hand>().unwrap()], 0.5);
+        assert_eq!(hands[&"AQs".parse::<Hand>().unwrap()], 0.375);
+        assert_eq!(hands.len(), 3);
+        assert_eq!(super::parse("AA:1,XYs:1"), Err(ParseRangeFileError(String::from("XYs:1"))));
+    }
+
+    #[test]
+    fn reads_percent_grids() {
+        let mut rows = vec![String::from("100%,50%,,,,,,,,,,,")];
+        rows.extend((1..13).map(|_| vec!["0"; 13].join(",")));
+        let hands = super::parse(&rows.join("\n")).unwrap();
+        assert_eq!(hands[&"AA".parse::<Hand>().unwrap()], 1.0);
+        assert_eq!(hands[&"AKs".parse::<Hand>().unwrap()], 0.5);
+        assert_eq!(hands.len(), 2);
+    }
+
+    #[test]
+    fn library_paths_follow_folders() {
+        let range = Range {
+            name: String::from("UTG: open"),
+            folder: String::from("6-max/Cash"),
+            ..Range::new()
+        };
+        assert_eq!(
+            super::library_paths(&[range.clone(), range, Range::new()], RangeFormat::Grid),
+            vec![
+                ["6-max", "Cash", "UTG_ open.csv"].iter().collect::<PathBuf>(),
+                ["6-max", "Cash", "UTG_ open 2.csv"].iter().collect::<PathBuf>(),
+                PathBuf::from("untitled range.csv"),
+            ]
+        );
+    }
+}
@@ -1,19 +1,46 @@
-use crate::{fileio, range, ranges_screen, review, study_screen, toolbar};
+use crate::{
+    chart, confirmation, equity, fileio, ordering, range, range_file, ranges_screen, review,
+    settings, shortcuts, statistics, statistics_screen, strategy, study_screen, toolbar,
+};
+use crate::hand::Hand;
+use crate::ordering::HandOrdering;
+use crate::review::scheduler::{Grade, Scheduler};
+use chrono::prelude::*;
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap};
+use std::collections::{BinaryHeap, HashMap};
 use std::error::Error;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use crate::messages::{Message, RangesMessage};
 
-use crate::ranges_screen::ActiveRange;
+use iced::{executor, Application, Column, Command, Container, Element, Subscription};
 
-use iced::{executor, Application, Column, Command, Container, Element};
+use iced_native::{Align, Length};
 
-use iced_native::Align;
-
-pub type ReviewItemsKey = (review::item::Presentation, review::item::Answer);
+pub type ReviewItemsKey = review::item::QuestionId;
 type ReviewItems = fileio::ReviewCollection;
 
+const EQUITY_QUESTION_SETTINGS: equity::Settings = equity::Settings {
+    exact_limit: 0,
+    trials: 1_000,
+    seed: 0,
+};
+
+const HEAT_MAP_SETTINGS: equity::Settings = equity::Settings {
+    exact_limit: 0,
+    trials: 2_000,
+    seed: 0,
+};
+
+// orderings rank hands only a fraction of a percent of equity apart, so
+// they need far more trials than the heat map to keep the order stable
+const ORDERING_SETTINGS: equity::Settings = equity::Settings {
+    exact_limit: 0,
+    trials: 100_000,
+    seed: 0,
+};
+
 
 
 
@@ -43,68 +70,184 @@ impl PartialEq for ScheduleItem {
 
 impl Eq for ScheduleItem {}
 
+#[derive(Default)]
 enum ScreenType {
     Study,
+    #[default]
     Ranges,
-}
-
-impl Default for ScreenType {
-    fn default() -> Self {
-        ScreenType::Ranges
-    }
+    Statistics,
 }
 
 pub struct RangeTrainer {
     ranges: Vec<range::Range>,
+    strategies: Vec<strategy::Strategy>,
     review_items: ReviewItems,
     toolbar: toolbar::ToolBar,
     ranges_screen: ranges_screen::RangesScreen,
     current_screen: ScreenType,
     study_screen: study_screen::StudyScreen,
+    statistics_screen: statistics_screen::StatisticsScreen,
     review_schedule: BinaryHeap<ScheduleItem>,
+    confirmation: Option<confirmation::Confirmation>,
+    settings: settings::Settings,
+    attempt: Option<Attempt>,
+    /// Equities for the equity questions by range ID, with the hash of the
+    /// hands they were worked out for.
+    equity_answers: HashMap<String, (u64, Vec<(Hand, f32)>)>,
+    /// Ranges whose equities are being worked out, with the hash of the
+    /// hands they are for.
+    pending_equity_answers: HashMap<String, u64>,
+    /// Set when the ranges may have changed, so the next update checks
+    /// which equities need working out again.
+    equity_answers_stale: bool,
+}
+
+/// The question on the study screen and, once it is answered, the answer
+/// given and how long it took, kept for the review log.
+struct Attempt {
+    key: ReviewItemsKey,
+    shown_at: Instant,
+    percent_overdue: f32,
+    answer: Option<(review::item::Answer, Duration)>,
+}
+
+/// The equity of each hand against `range` in percent, leaving out hands
+/// that never meet it.
+fn equity_answers(range: &range::Range) -> Vec<(Hand, f32)> {
+    Hand::all()
+        .iter()
+        .filter_map(|hand| {
+            let equity = equity::hand_vs_range(*hand, range, &[], &EQUITY_QUESTION_SETTINGS);
+            if equity.total() == 0.0 {
+                None
+            } else {
+                Some((*hand, equity.equity_percent()))
+            }
+        })
+        .collect()
+}
+
+/// What was loaded, or the default with what went wrong added to `errors`.
+fn loaded<T: Default>(what: &str, result: Result<T, Box<dyn Error>>, errors: &mut Vec<String>) -> T {
+    result.unwrap_or_else(|e| {
+        errors.push(format!("{} failed: {}", what, e));
+        T::default()
+    })
+}
+
+/// Every review item, most overdue first.
+fn schedule(review_items: &ReviewItems, scheduler: &dyn Scheduler) -> BinaryHeap<ScheduleItem> {
+    let now = Local::now();
+    review_items
+        .iter()
+        .map(|(key, review_item)| ScheduleItem {
+            key: key.clone(),
+            percent_overdue: scheduler.percent_overdue(review_item, now),
+        })
+        .collect()
 }
 
 impl RangeTrainer {
     fn rebuild_review_items(&mut self) -> Result<(), Box<dyn Error>> {
-        let mut review_items = ReviewItems::new();
+        self.equity_answers_stale = true;
+        let review_items = self.review_items_for_ranges();
+        self.review_schedule = schedule(&review_items, &*self.settings.scheduler.scheduler());
+        self.review_items = review_items;
+        self.show_next_question();
+        fileio::save_review_items(&self.review_items)
+    }
+
+    /// A review item for every question about the ranges and strategies.
+    /// Legacy items no question has taken over yet are kept.
+    fn review_items_for_ranges(&self) -> ReviewItems {
+        let mut review_items = self.review_items.legacy_only();
 
         // binary range questions
         for range in self.ranges.iter() {
             for hand in crate::hand::Hand::all().iter() {
-                let key = (
-                    review::item::Presentation::TextHand(review::item::DisplayString::new(&range.name), *hand),
-                    if range.contains(hand) {review::item::Answer::Yes} else {review::item::Answer::No},
-                );
-                match self.review_items.get(&key) {
-                    Some(review_item) => {
-                        review_items.insert(key.clone(), review_item.clone());
+                self.carry_over(&mut review_items, review::item::Question {
+                    id: review::item::QuestionId(format!("range/{}/contains/{}", range.id, hand)),
+                    presentation: review::item::Presentation::TextHand(review::item::DisplayString::new(&range.label()), *hand),
+                    options: review::item::Options::Binary,
+                    answer: if range.contains(hand) {review::item::Answer::Yes} else {review::item::Answer::No},
+                    tolerance: None,
+                });
+            }
+        }
+
+        // hand vs range equity questions, worked out in the background. Until
+        // a range's equities are in its questions stay as they were.
+        for range in self.ranges.iter().filter(|r| !r.hands.is_empty()) {
+            let hands_hash = range.hands_hash();
+            match self.equity_answers.get(&range.id).filter(|(hash, _)| *hash == hands_hash) {
+                Some((_, answers)) => {
+                    for (hand, equity) in answers.iter() {
+                        self.carry_over(&mut review_items, review::item::Question {
+                            id: review::item::QuestionId(format!("range/{}/equity/{}", range.id, hand)),
+                            presentation: review::item::Presentation::TextHand(
+                                review::item::DisplayString::new(&format!("Equity vs {}", range.label())),
+                                *hand,
+                            ),
+                            options: review::item::Options::Numbers,
+                            answer: review::item::Answer::Text(review::item::DisplayString(format!("{:.1}", equity))),
+                            tolerance: Some(5.0),
+                        });
                     }
-                    None => {
-                        review_items.insert(
-                            key.clone(),
-                            review::item::ReviewItem {
-                                difficulty: 0.3,
-                                days_between_review_attempts: 3.0,
-                                date_last_reviewed: None,
-                                last_answer_correct: false,
-                                question: review::item::Question {
-                                    presentation: key.0,
-                                    options: review::item::Options::Binary,
-                                    answer: key.1,
-                                    tolerance: None,
-                                },
-                            },
-                        );
+                }
+                None => {
+                    let prefix = format!("range/{}/equity/", range.id);
+                    for (key, review_item) in self.review_items.iter().filter(|(key, _)| key.0.starts_with(&prefix)) {
+                        review_items.insert(key.clone(), review_item.clone());
                     }
                 }
             }
         }
 
+        // blocker questions, holding one combo of each hand in the range
+        for range in self.ranges.iter().filter(|r| !r.hands.is_empty()) {
+            let notation = range.notation();
+            for hand in crate::hand::Hand::all().iter().filter(|h| range.contains(h)) {
+                let id = review::item::QuestionId(format!("range/{}/blockers/{}", range.id, hand));
+                let hole = *id.pick(&hand.combos());
+                let live = range.live_combo_count(&hole.cards());
+                self.carry_over(&mut review_items, review::item::Question {
+                    id,
+                    presentation: review::item::Presentation::Text(review::item::DisplayString::new(&format!(
+                        "Villain's range is {}. You hold {}. How many combos remain?",
+                        notation, hole
+                    ))),
+                    options: review::item::Options::Numbers,
+                    answer: review::item::Answer::Text(review::item::DisplayString(format!(
+                        "{}",
+                        (live * 10.0).round() / 10.0
+                    ))),
+                    tolerance: Some(0.5),
+                });
+            }
+        }
+
+        // strategy action questions
+        for strategy in self.strategies.iter().filter(|s| !s.actions.is_empty()) {
+            for hand in crate::hand::Hand::all().iter() {
+                self.carry_over(&mut review_items, review::item::Question {
+                    id: review::item::QuestionId(format!("strategy/{}/action/{}", strategy.id, hand)),
+                    presentation: review::item::Presentation::TextHand(
+                        review::item::DisplayString::new(&format!("Action in {}", strategy.name)),
+                        *hand,
+                    ),
+                    options: review::item::Options::PokerAction,
+                    answer: review::item::Answer::PokerAction(strategy.preferred_action(hand)),
+                    tolerance: Some(0.5),
+                });
+            }
+        }
+
         // required fold equity questions
         for bet in (2..=200).step_by(4) {
             for pot in (3..=200).step_by(4) {
-                let key = (
-                    review::item::Presentation::Text(
+                self.carry_over(&mut review_items, review::item::Question {
+                    id: review::item::QuestionId(format!("rfe/{}/{}", bet, pot)),
+                    presentation: review::item::Presentation::Text(
                         review::item::DisplayString(format!(
                             "RFE for {}BB bet to win {}BB pot",
                             bet as f32 / 2.0,
@@ -112,115 +255,155 @@ impl RangeTrainer {
                         )
                         .to_string()),
                     ),
-                    review::item::Answer::Text(review::item::DisplayString((bet as f32 / (bet as f32 + pot as f32) * 100.0).to_string())),
-                );
-                match self.review_items.get(&key) {
-                    Some(review_item) => {
-                        review_items.insert(key.clone(), review_item.clone());
-                    }
-                    None => {
-                        review_items.insert(
-                            key.clone(),
-                            review::item::ReviewItem {
-                                difficulty: 0.3,
-                                days_between_review_attempts: 3.0,
-                                date_last_reviewed: None,
-                                last_answer_correct: false,
-                                question: review::item::Question {
-                                    presentation: key.0,
-                                    options: review::item::Options::Numbers,
-                                    answer: key.1,
-                                    tolerance: Some(9.6),
-                                },
-                            },
-                        );
-                    }
-                }
+                    options: review::item::Options::Numbers,
+                    answer: review::item::Answer::Text(review::item::DisplayString((bet as f32 / (bet as f32 + pot as f32) * 100.0).to_string())),
+                    tolerance: Some(9.6),
+                });
             }
         }
 
-        let mut review_schedule: BinaryHeap<ScheduleItem> = BinaryHeap::<ScheduleItem>::new();
-        for (key, review_item) in review_items.iter() {
-            review_schedule.push(ScheduleItem {
-                key: key.clone(),
-                percent_overdue: review_item.percent_overdue(),
-            });
-        }
+        review_items
+    }
 
-        let study_screen = match review_schedule.pop() {
-            Some(schedule_item) => {
-                study_screen::StudyScreen::new(Some(review::display::ReviewDisplay::new(
-                    review_items.get(&schedule_item.key).unwrap().clone(),
-                )))
+    /// Starts working out equities in the background for the ranges whose
+    /// hands have changed since theirs were.
+    fn update_equity_answers(&mut self) -> Command<Message> {
+        self.equity_answers_stale = false;
+        let ranges = &self.ranges;
+        self.equity_answers.retain(|id, _| range::find(ranges, id).is_some());
+        let mut commands = vec![];
+        for range in self.ranges.iter().filter(|r| !r.hands.is_empty()) {
+            let hands_hash = range.hands_hash();
+            let cached = self.equity_answers.get(&range.id).map(|(hash, _)| *hash);
+            if cached == Some(hands_hash) || self.pending_equity_answers.get(&range.id) == Some(&hands_hash) {
+                continue;
             }
-            None => study_screen::StudyScreen::new(None),
-        };
-
-        self.study_screen = study_screen;
-        self.review_schedule = review_schedule;
-
-        self.review_items = review_items;
-        fileio::save_review_items(&self.review_items)
+            self.pending_equity_answers.insert(range.id.clone(), hands_hash);
+            let range = range.clone();
+            commands.push(Command::perform(
+                async move { (range.id.clone(), equity_answers(&range)) },
+                move |(range_id, answers)| Message::EquityAnswersComputed(range_id, hands_hash, answers),
+            ));
+        }
+        Command::batch(commands)
     }
-}
 
-impl Application for RangeTrainer {
-    type Executor = executor::Default;
-    type Message = Message;
-    type Flags = ();
-
-    fn new(_flags: ()) -> (Self, Command<Self::Message>) {
-        let ranges = fileio::load_ranges();
-        let review_items = fileio::load_review_items();
-        let mut review_schedule: BinaryHeap<ScheduleItem> = BinaryHeap::<ScheduleItem>::new();
-
-        // schedule review items on startup
-        for (key, review_item) in review_items.iter() {
-            review_schedule.push(ScheduleItem {
-                key: key.clone(),
-                percent_overdue: review_item.percent_overdue(),
-            });
-        }
+    /// Shows what went wrong on the status line.
+    fn report_error(&mut self, what: &str, e: Box<dyn Error>) {
+        self.ranges_screen
+            .update(RangesMessage::FileStatus(format!("{} failed: {}", what, e)));
+    }
 
-        // select first review item for study
-        let study_screen = match review_schedule.pop() {
+    fn show_next_question(&mut self) {
+        self.attempt = None;
+        self.study_screen = match self.review_schedule.pop() {
             Some(schedule_item) => {
+                self.attempt = Some(Attempt {
+                    key: schedule_item.key.clone(),
+                    shown_at: Instant::now(),
+                    percent_overdue: schedule_item.percent_overdue,
+                    answer: None,
+                });
                 study_screen::StudyScreen::new(Some(review::display::ReviewDisplay::new(
-                    review_items.get(&schedule_item.key).unwrap().clone(),
+                    self.review_items.get(&schedule_item.key).unwrap().clone(),
                 )))
             }
             None => study_screen::StudyScreen::new(None),
         };
+    }
 
-        let mut ranges_screen = ranges_screen::RangesScreen::new();
-        ranges_screen.update(RangesMessage::UpdateSelectRangeButtons(ranges.to_vec()));
-
-        (
-            Self {
-                ranges: ranges,
-                review_items,
-                review_schedule,
-                ranges_screen: ranges_screen,
-                study_screen: study_screen,
-                toolbar: toolbar::ToolBar::default(),
-                current_screen: ScreenType::default(),
+    /// Adds `question` to `review_items`, keeping the history of the item
+    /// already saved for it. Items saved before questions had IDs are found
+    /// by the wording and answer they were saved with. The question itself is
+    /// always replaced so renamed ranges and changed answers show up.
+    fn carry_over(&self, review_items: &mut ReviewItems, question: review::item::Question) {
+        let legacy_key = (question.presentation.clone(), question.answer.clone());
+        let review_item = match self
+            .review_items
+            .get(&question.id)
+            .or_else(|| self.review_items.legacy(&legacy_key))
+        {
+            Some(review_item) => review::item::ReviewItem {
+                question,
+                ..review_item.clone()
+            },
+            None => review::item::ReviewItem {
+                difficulty: 0.3,
+                days_between_review_attempts: 3.0,
+                date_last_reviewed: None,
+                last_answer_correct: false,
+                scheduler_state: Default::default(),
+                question,
             },
-            Command::none(),
-        )
+        };
+        review_items.insert(review_item.question.id.clone(), review_item);
+        review_items.remove_legacy(&legacy_key);
     }
 
-    fn title(&self) -> String {
-        String::from("Range Trainer")
+    /// Confirmation needed before `message` runs, if it would throw away
+    /// unsaved edits to the active range or delete it.
+    fn confirmation_for(&self, message: &Message) -> Option<confirmation::Confirmation> {
+        let active_range = self.ranges_screen.active_range.as_ref()?;
+        match message {
+            Message::DeleteRange(Some(_)) => Some(confirmation::Confirmation::delete(
+                &active_range.range,
+                message.clone(),
+            )),
+            Message::RangeSelected(_)
+            | Message::CreateNewRange
+            | Message::CopyRange(Some(_))
+            | Message::CreateNewStrategy
+            | Message::StrategySelected(_)
+            | Message::ViewStudyScreen
+            | Message::ViewStatisticsScreen
+            | Message::ImportRanges(_)
+                if active_range.is_dirty() =>
+            {
+                Some(confirmation::Confirmation::unsaved(&active_range.range, message.clone()))
+            }
+            _ => None,
+        }
     }
 
-    fn update(&mut self, _message: Self::Message) -> Command<Self::Message> {
+    /// Swaps a range with the one before or after it in the same folder.
+    fn move_range(&mut self, range_id: usize, up: bool) {
+        let folder = match self.ranges.get(range_id) {
+            Some(range) => range.folder_path().join("/"),
+            None => return,
+        };
+        let same_folder = |id: &usize| self.ranges[*id].folder_path().join("/") == folder;
+        let neighbour = if up {
+            (0..range_id).rev().find(same_folder)
+        } else {
+            (range_id + 1..self.ranges.len()).find(same_folder)
+        };
+        let other_id = match neighbour {
+            Some(other_id) => other_id,
+            None => return,
+        };
+        self.ranges.swap(range_id, other_id);
+        let swapped = |id: usize| match id {
+            id if id == range_id => other_id,
+            id if id == other_id => range_id,
+            id => id,
+        };
+        if let Some(active_range) = &mut self.ranges_screen.active_range {
+            active_range.id = swapped(active_range.id);
+        }
+        self.ranges_screen
+            .update(RangesMessage::UpdateSelectRangeButtons(
+                self.ranges.to_vec(),
+            ));
+        if let Err(e) = fileio::save_ranges(&self.ranges) {
+            self.report_error("Saving ranges", e);
+        }
+    }
+
+    fn perform(&mut self, _message: Message) -> Command<Message> {
         match _message {
             Message::SaveRanges => {
-                match fileio::save_ranges(&self.ranges) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        dbg!(e);
-                    }
+                if let Err(e) = fileio::save_ranges(&self.ranges) {
+                    self.report_error("Saving ranges", e);
                 }
                 self.ranges_screen
                     .update(RangesMessage::RangesHaveBeenSaved);
@@ -231,6 +414,21 @@ impl Application for RangeTrainer {
             Message::ViewRangesScreen => {
                 self.current_screen = ScreenType::Ranges;
             }
+            Message::ViewStatisticsScreen => {
+                let names = self
+                    .ranges
+                    .iter()
+                    .map(|range| (range.id.clone(), range.name.clone()))
+                    .chain(self.strategies.iter().map(|strategy| (strategy.id.clone(), strategy.name.clone())))
+                    .collect();
+                self.statistics_screen.set_statistics(statistics::Statistics::compute(
+                    &fileio::load_review_log(),
+                    self.review_items.iter().map(|(_, review_item)| review_item),
+                    &names,
+                    Local::now(),
+                ));
+                self.current_screen = ScreenType::Statistics;
+            }
             Message::CreateNewRange => {
                 let new_range = range::Range::new();
                 self.ranges_screen
@@ -240,23 +438,16 @@ impl Application for RangeTrainer {
                     .update(RangesMessage::UpdateSelectRangeButtons(
                         self.ranges.to_vec(),
                     ));
-                match fileio::save_ranges(&self.ranges) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        dbg!(e);
-                    }
+                if let Err(e) = fileio::save_ranges(&self.ranges) {
+                    self.report_error("Saving ranges", e);
                 }
-                match self.rebuild_review_items() {
-                    Ok(_) => {}
-                    Err(e) => {
-                        dbg!(e);
-                    }
+                if let Err(e) = self.rebuild_review_items() {
+                    self.report_error("Saving review items", e);
                 }
             }
             Message::RangeSelected(range_id) => match self.ranges.get(range_id) {
                 Some(range) => {
-                    self.ranges_screen.active_range =
-                        Some(ActiveRange::new(range_id, range.clone()));
+                    self.ranges_screen.set_range(range_id, range.clone());
                 }
                 None => {}
             },
@@ -268,17 +459,11 @@ impl Application for RangeTrainer {
                         .update(RangesMessage::UpdateSelectRangeButtons(
                             self.ranges.to_vec(),
                         ));
-                    match fileio::save_ranges(&self.ranges) {
-                        Ok(_) => {}
-                        Err(e) => {
-                            dbg!(e);
-                        }
+                    if let Err(e) = fileio::save_ranges(&self.ranges) {
+                        self.report_error("Saving ranges", e);
                     }
-                    match self.rebuild_review_items() {
-                        Ok(_) => {}
-                        Err(e) => {
-                            dbg!(e);
-                        }
+                    if let Err(e) = self.rebuild_review_items() {
+                        self.report_error("Saving review items", e);
                     }
                     self.ranges_screen
                         .update(RangesMessage::RangesHaveBeenSaved);
@@ -289,8 +474,8 @@ impl Application for RangeTrainer {
                 self.ranges.remove(active_range.id);
 
                 if let Some(last_range) = self.ranges.last() {
-                    self.ranges_screen.active_range =
-                        Some(ActiveRange::new(self.ranges.len() - 1, last_range.clone()));
+                    self.ranges_screen
+                        .set_range(self.ranges.len() - 1, last_range.clone());
                 } else {
                     self.ranges_screen.active_range = None;
                 }
@@ -299,60 +484,314 @@ impl Application for RangeTrainer {
                     .update(RangesMessage::UpdateSelectRangeButtons(
                         self.ranges.to_vec(),
                     ));
-                match fileio::save_ranges(&self.ranges) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        dbg!(e);
-                    }
+                if let Err(e) = fileio::save_ranges(&self.ranges) {
+                    self.report_error("Saving ranges", e);
                 }
-                match self.rebuild_review_items() {
-                    Ok(_) => {}
-                    Err(e) => {
-                        dbg!(e);
-                    }
+                if let Err(e) = self.rebuild_review_items() {
+                    self.report_error("Saving review items", e);
                 }
                 self.ranges_screen
                     .update(RangesMessage::RangesHaveBeenSaved);
             }
             Message::DeleteRange(None) => {}
+            Message::ImportRanges(path) => {
+                let status = match fileio::import_ranges(Path::new(&path)) {
+                    Ok(imported) if imported.is_empty() => format!("No range files found in {}", path),
+                    Ok(imported) => {
+                        let count = imported.len();
+                        self.ranges_screen
+                            .set_range(self.ranges.len(), imported[0].clone());
+                        self.ranges.extend(imported);
+                        self.ranges_screen
+                            .update(RangesMessage::UpdateSelectRangeButtons(
+                                self.ranges.to_vec(),
+                            ));
+                        if let Err(e) = fileio::save_ranges(&self.ranges) {
+                            self.report_error("Saving ranges", e);
+                        }
+                        if let Err(e) = self.rebuild_review_items() {
+                            self.report_error("Saving review items", e);
+                        }
+                        format!("Imported {} ranges", count)
+                    }
+                    Err(e) => format!("Import failed: {}", e),
+                };
+                self.ranges_screen.update(RangesMessage::FileStatus(status));
+            }
+            Message::ExportRange(active_range, path, format) => {
+                let mut path = Path::new(&path).to_path_buf();
+                if path.is_dir() {
+                    path.push(range_file::file_name(&active_range.range, format));
+                }
+                let status = match fileio::export_range(&path, &active_range.range, format) {
+                    Ok(_) => format!("Exported {} to {}", active_range.range.name, path.display()),
+                    Err(e) => format!("Export failed: {}", e),
+                };
+                self.ranges_screen.update(RangesMessage::FileStatus(status));
+            }
+            Message::ExportChart(path) => {
+                let chart = if let Some(active_range) = &self.ranges_screen.active_range {
+                    Some((active_range.range.name.to_string(), chart::range_svg(&active_range.range)))
+                } else {
+                    self.ranges_screen.active_strategy.as_ref().map(|active_strategy| {
+                        (
+                            active_strategy.strategy.name.to_string(),
+                            chart::strategy_svg(&active_strategy.strategy),
+                        )
+                    })
+                };
+                if let Some((name, svg)) = chart {
+                    let mut path = Path::new(&path).to_path_buf();
+                    if path.is_dir() {
+                        path.push(chart::file_name(&name));
+                    }
+                    let status = match fileio::export_chart(&path, &svg) {
+                        Ok(_) => format!("Saved chart of {} to {}", name, path.display()),
+                        Err(e) => format!("Export failed: {}", e),
+                    };
+                    self.ranges_screen.update(RangesMessage::FileStatus(status));
+                }
+            }
+            Message::SelectHandOrdering(kind) => {
+                let villain = match kind {
+                    ordering::OrderingKind::EquityVsRange => {
+                        match self.ranges_screen.compare_range.as_ref().and_then(|id| range::find(&self.ranges, id)) {
+                            Some(villain) => Some(villain.clone()),
+                            None => {
+                                // keep the current ordering rather than label one that never arrives
+                                self.ranges_screen.update(RangesMessage::FileStatus(String::from(
+                                    "Pick a range with the vs button to order by equity against it",
+                                )));
+                                return Command::none();
+                            }
+                        }
+                    }
+                    _ => None,
+                };
+                let label = match &villain {
+                    Some(villain) => format!("equity vs {}", villain.name),
+                    None => kind.to_string(),
+                };
+                self.ranges_screen
+                    .update(RangesMessage::HandOrderingSelected(kind, label.clone()));
+                // equity orderings run every hand to showdown, so they are
+                // worked out in the background
+                let computed = move |order| Message::RangesScreen(RangesMessage::HandOrderComputed(label.clone(), order));
+                match villain {
+                    Some(villain) => {
+                        return Command::perform(
+                            async move { ordering::EquityVsRange::new(&villain, &ORDERING_SETTINGS).order() },
+                            computed,
+                        )
+                    }
+                    None => match kind {
+                        ordering::OrderingKind::SklanskyMalmuth => {
+                            return self.perform(computed(ordering::SklanskyMalmuth.order()));
+                        }
+                        ordering::OrderingKind::EquityVsRandom => {
+                            return Command::perform(
+                                async { ordering::EquityVsRange::random(&ORDERING_SETTINGS).order() },
+                                computed,
+                            )
+                        }
+                        _ => return self.perform(computed(ordering::Chen.order())),
+                    },
+                }
+            }
+            Message::ExportLibrary(path, format) => {
+                let status = match fileio::export_library(Path::new(&path), &self.ranges, format) {
+                    Ok(_) => format!("Exported {} ranges to {}", self.ranges.len(), path),
+                    Err(e) => format!("Export failed: {}", e),
+                };
+                self.ranges_screen.update(RangesMessage::FileStatus(status));
+            }
+            Message::MoveRangeUp(range_id) => self.move_range(range_id, true),
+            Message::MoveRangeDown(range_id) => self.move_range(range_id, false),
             Message::CopyRange(Some(active_range)) => {
                 let mut copied_range = range::Range::from(active_range);
+                copied_range.id = range::new_id();
                 copied_range.name.push_str(" Copy");
-                self.ranges_screen.active_range =
-                    Some(ActiveRange::new(self.ranges.len(), copied_range.clone()));
+                self.ranges_screen
+                    .set_range(self.ranges.len(), copied_range.clone());
                 self.ranges.push(copied_range);
                 self.ranges_screen
                     .update(RangesMessage::UpdateSelectRangeButtons(
                         self.ranges.to_vec(),
                     ));
-                match fileio::save_ranges(&self.ranges) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        dbg!(e);
-                    }
+                if let Err(e) = fileio::save_ranges(&self.ranges) {
+                    self.report_error("Saving ranges", e);
                 }
-                match self.rebuild_review_items() {
-                    Ok(_) => {}
-                    Err(e) => {
-                        dbg!(e);
-                    }
+                if let Err(e) = self.rebuild_review_items() {
+                    self.report_error("Saving review items", e);
                 }
                 self.ranges_screen
                     .update(RangesMessage::RangesHaveBeenSaved);
             }
             Message::CopyRange(None) => {}
+            Message::CreateNewStrategy => {
+                let new_strategy = strategy::Strategy::new();
+                self.ranges_screen
+                    .set_strategy(self.strategies.len(), new_strategy.clone());
+                self.strategies.push(new_strategy);
+                self.ranges_screen
+                    .update(RangesMessage::UpdateSelectStrategyButtons(
+                        self.strategies.to_vec(),
+                    ));
+                if let Err(e) = fileio::save_strategies(&self.strategies) {
+                    self.report_error("Saving strategies", e);
+                }
+            }
+            Message::StrategySelected(strategy_id) => {
+                if let Some(strategy) = self.strategies.get(strategy_id) {
+                    self.ranges_screen.set_strategy(strategy_id, strategy.clone());
+                }
+            }
+            Message::SaveStrategy(Some(active_strategy)) => {
+                if let Some(s) = self.strategies.get_mut(active_strategy.id) {
+                    *s = active_strategy.strategy.clone();
+                    self.ranges_screen
+                        .update(RangesMessage::UpdateSelectStrategyButtons(
+                            self.strategies.to_vec(),
+                        ));
+                    if let Err(e) = fileio::save_strategies(&self.strategies) {
+                        self.report_error("Saving strategies", e);
+                    }
+                    if let Err(e) = self.rebuild_review_items() {
+                        self.report_error("Saving review items", e);
+                    }
+                    self.ranges_screen
+                        .update(RangesMessage::StrategiesHaveBeenSaved);
+                }
+            }
+            Message::SaveStrategy(None) => {}
+            Message::DeleteStrategy(Some(active_strategy)) => {
+                self.strategies.remove(active_strategy.id);
+                self.ranges_screen.active_strategy = None;
+                self.ranges_screen
+                    .update(RangesMessage::UpdateSelectStrategyButtons(
+                        self.strategies.to_vec(),
+                    ));
+                if let Err(e) = fileio::save_strategies(&self.strategies) {
+                    self.report_error("Saving strategies", e);
+                }
+                if let Err(e) = self.rebuild_review_items() {
+                    self.report_error("Saving review items", e);
+                }
+            }
+            Message::DeleteStrategy(None) => {}
+            Message::ResolveConfirmation(resolution) => {
+                if let Some(confirmation) = self.confirmation.take() {
+                    // the held back message acts on the range the confirmation
+                    // was about, as it is once resolved
+                    let active_range = self.ranges_screen.active_range.clone();
+                    let index = match &active_range {
+                        Some(active_range) if active_range.range.id == confirmation.range_id => active_range.id,
+                        _ => return Command::none(),
+                    };
+                    match resolution {
+                        confirmation::Resolution::Save => {
+                            self.perform(Message::SaveRange(active_range));
+                            return match confirmation.message {
+                                Message::CopyRange(Some(_)) => {
+                                    self.perform(Message::CopyRange(self.ranges_screen.active_range.clone()))
+                                }
+                                message => self.perform(message),
+                            };
+                        }
+                        confirmation::Resolution::Discard => {
+                            if let Some(range) = self.ranges.get(index).cloned() {
+                                self.ranges_screen.set_range(index, range);
+                            }
+                            return match confirmation.message {
+                                Message::CopyRange(Some(_)) => {
+                                    self.perform(Message::CopyRange(self.ranges_screen.active_range.clone()))
+                                }
+                                Message::DeleteRange(Some(_)) => {
+                                    self.perform(Message::DeleteRange(self.ranges_screen.active_range.clone()))
+                                }
+                                message => self.perform(message),
+                            };
+                        }
+                        confirmation::Resolution::Cancel => {}
+                    }
+                }
+            }
+            Message::Shortcut(shortcut) => {
+                if let ScreenType::Ranges = self.current_screen {
+                    self.ranges_screen.update(match shortcut {
+                        shortcuts::Shortcut::Undo => RangesMessage::Undo,
+                        shortcuts::Shortcut::Redo => RangesMessage::Redo,
+                    });
+                }
+            }
+            Message::CombineRanges(active_range, other_id, operation) => {
+                if let Some(other) = range::find(&self.ranges, &other_id) {
+                    let combined = active_range.range.combine(other, operation);
+                    self.ranges_screen
+                        .update(RangesMessage::RangesCombined(active_range.id, combined));
+                }
+            }
+            Message::CompareRanges(other_id) => {
+                if let Some(other) = range::find(&self.ranges, &other_id) {
+                    self.ranges_screen
+                        .update(RangesMessage::ComparisonRangeLoaded(other.clone()));
+                }
+            }
+            Message::ComputeRangeEquity(active_range, villain_id) => {
+                if let Some(villain) = range::find(&self.ranges, &villain_id) {
+                    let hero = active_range.range.clone();
+                    let villain = villain.clone();
+                    let hero_id = active_range.id;
+                    return Command::perform(
+                        async move { equity::range_vs_range(&hero, &villain, &[], &HEAT_MAP_SETTINGS) },
+                        move |range_equity| {
+                            Message::RangesScreen(RangesMessage::RangeEquityComputed(hero_id, range_equity))
+                        },
+                    );
+                }
+            }
+            Message::EquityAnswersComputed(range_id, hands_hash, answers) => {
+                if self.pending_equity_answers.get(&range_id) == Some(&hands_hash) {
+                    self.pending_equity_answers.remove(&range_id);
+                }
+                if !matches!(range::find(&self.ranges, &range_id), Some(range) if range.hands_hash() == hands_hash) {
+                    return Command::none();
+                }
+                self.equity_answers.insert(range_id, (hands_hash, answers));
+                self.review_items = self.review_items_for_ranges();
+                // the question on the study screen stays up, so it is left
+                // out of the schedule until it is graded
+                let scheduler = self.settings.scheduler.scheduler();
+                let shown = self.attempt.as_ref().map(|attempt| attempt.key.clone());
+                match shown.filter(|key| self.review_items.get(key).is_some()) {
+                    Some(shown) => {
+                        self.review_schedule = schedule(&self.review_items, &*scheduler)
+                            .into_iter()
+                            .filter(|schedule_item| schedule_item.key != shown)
+                            .collect();
+                    }
+                    None => {
+                        self.review_schedule = schedule(&self.review_items, &*scheduler);
+                        self.show_next_question();
+                    }
+                }
+                if let Err(e) = fileio::save_review_items(&self.review_items) {
+                    self.report_error("Saving review items", e);
+                }
+            }
             Message::AnswerReviewItem(key, ans) => {
-                let review_item = self.review_items.get_mut(&key).unwrap();
-                let correct = match ans {
-                    review::item::Answer::Yes => ans == review_item.question.answer,
-                    review::item::Answer::No => ans == review_item.question.answer,
+                let given_answer = ans.clone();
+                let review_item = self.review_items.get(&key).unwrap();
+                let grade = match ans {
+                    review::item::Answer::Yes => Grade::from_correct(ans == review_item.question.answer),
+                    review::item::Answer::No => Grade::from_correct(ans == review_item.question.answer),
                     review::item::Answer::Text(s) => {
                         if let Ok(num) = s.parse::<f32>() {
                             if let review::item::Answer::Text(x) =
                                 review_item.question.answer.clone()
                             {
                                 if let Ok(ans_num) = x.parse::<f32>() {
-                                    (num - ans_num).abs() < review_item.question.tolerance.unwrap()
+                                    Grade::from_error(num - ans_num, review_item.question.tolerance.unwrap())
                                 } else {
                                     return Command::none();
                                 }
@@ -364,13 +803,13 @@ impl Application for RangeTrainer {
                         }
                     }
                     review::item::Answer::PokerAction(review::item::PokerAction::Fold) => {
-                        ans == review_item.question.answer
+                        Grade::from_correct(ans == review_item.question.answer)
                     }
                     review::item::Answer::PokerAction(review::item::PokerAction::Check) => {
-                        ans == review_item.question.answer
+                        Grade::from_correct(ans == review_item.question.answer)
                     }
                     review::item::Answer::PokerAction(review::item::PokerAction::Call) => {
-                        ans == review_item.question.answer
+                        Grade::from_correct(ans == review_item.question.answer)
                     }
                     review::item::Answer::PokerAction(review::item::PokerAction::Raise(s)) => {
                         if let Ok(num) = s.parse::<f32>() {
@@ -379,7 +818,7 @@ impl Application for RangeTrainer {
                             ) = review_item.question.answer.clone()
                             {
                                 if let Ok(ans_num) = x.parse::<f32>() {
-                                    (num - ans_num).abs() < review_item.question.tolerance.unwrap()
+                                    Grade::from_error(num - ans_num, review_item.question.tolerance.unwrap())
                                 } else {
                                     return Command::none();
                                 }
@@ -391,41 +830,218 @@ impl Application for RangeTrainer {
                         }
                     }
                 };
-                review_item.update(correct);
+                if let Some(attempt) = &mut self.attempt {
+                    attempt.answer = Some((given_answer, attempt.shown_at.elapsed()));
+                }
+                self.study_screen.update(review::display::ReviewDisplayMessage::AnswerSubmitted(grade));
+            }
+            Message::GradeReviewItem(key, grade) => {
+                let scheduler = self.settings.scheduler.scheduler();
+                let review_item = self.review_items.get_mut(&key).unwrap();
+                let now = Local::now();
+                scheduler.update(review_item, grade, now);
                 self.review_schedule.push(ScheduleItem {
-                    key: (review_item.question.presentation.clone(), review_item.question.answer.clone()),
-                    percent_overdue: review_item.percent_overdue(),
+                    key: review_item.question.id.clone(),
+                    percent_overdue: scheduler.percent_overdue(review_item, now),
                 });
-                self.study_screen.update(review::display::ReviewDisplayMessage::AnswerSubmitted(correct));
-                
+                if let Some(Attempt { answer: Some((given_answer, response_time)), percent_overdue, .. }) =
+                    self.attempt.take()
+                {
+                    let entry = review::log::ReviewLogEntry {
+                        question_id: key,
+                        timestamp: now,
+                        given_answer,
+                        correct_answer: review_item.question.answer.clone(),
+                        grade,
+                        response_time_ms: response_time.as_millis() as u64,
+                        scheduler: self.settings.scheduler,
+                        percent_overdue,
+                        days_between_review_attempts: review_item.days_between_review_attempts,
+                    };
+                    if let Err(e) = fileio::append_review_log(&entry) {
+                        self.report_error("Saving the review log", e);
+                    }
+                }
+                if let Err(e) = fileio::save_review_items(&self.review_items) {
+                    self.report_error("Saving review items", e);
+                }
+                self.show_next_question();
+            }
+            Message::SelectScheduler(kind) => {
+                self.settings.scheduler = kind;
+                if let Err(e) = fileio::save_settings(&self.settings) {
+                    self.report_error("Saving settings", e);
+                }
+                // start the new scheduler from what the old one learned
+                let scheduler = kind.scheduler();
+                for review_item in self.review_items.values_mut() {
+                    scheduler.seed(review_item);
+                }
+                if let Err(e) = fileio::save_review_items(&self.review_items) {
+                    self.report_error("Saving review items", e);
+                }
+                self.review_schedule = schedule(&self.review_items, &*scheduler);
+                self.show_next_question();
             }
             Message::ReviewDisplayMessage(_message) => {
                 self.study_screen.update(_message);
             }
-            Message::RequestNewQuestion => {
-                self.study_screen = match self.review_schedule.pop() {
-                    Some(schedule_item) => {
-                        study_screen::StudyScreen::new(Some(review::display::ReviewDisplay::new(
-                            self.review_items.get(&schedule_item.key).unwrap().clone(),
-                        )))
-                    }
-                    None => study_screen::StudyScreen::new(None),
-                };
-            }
+            Message::RequestNewQuestion => self.show_next_question(),
         }
         Command::none()
     }
+}
+
+impl Application for RangeTrainer {
+    type Executor = executor::Default;
+    type Message = Message;
+    type Flags = ();
+
+    fn new(_flags: ()) -> (Self, Command<Self::Message>) {
+        let mut errors = vec![];
+        let mut ranges = loaded("Loading ranges", fileio::load_ranges(), &mut errors);
+        let mut strategies = loaded("Loading strategies", fileio::load_strategies(), &mut errors);
+        let review_items = loaded("Loading review items", fileio::load_review_items(), &mut errors);
+        let settings = loaded("Loading settings", fileio::load_settings(), &mut errors);
+
+        // give ranges and strategies saved before IDs existed one for good
+        let mut migrated = false;
+        for id in ranges
+            .iter_mut()
+            .map(|r| &mut r.id)
+            .chain(strategies.iter_mut().map(|s| &mut s.id))
+            .filter(|id| id.is_empty())
+        {
+            *id = range::new_id();
+            migrated = true;
+        }
+        if migrated {
+            if let Err(e) = fileio::save_ranges(&ranges).and(fileio::save_strategies(&strategies)) {
+                errors.push(format!("Saving ranges and strategies failed: {}", e));
+            }
+        }
+        // schedule review items on startup
+        let review_schedule = schedule(&review_items, &*settings.scheduler.scheduler());
+
+        let mut ranges_screen = ranges_screen::RangesScreen::new();
+        ranges_screen.update(RangesMessage::UpdateSelectRangeButtons(ranges.to_vec()));
+        ranges_screen.update(RangesMessage::UpdateSelectStrategyButtons(strategies.to_vec()));
+
+        let mut range_trainer = Self {
+            ranges: ranges,
+            strategies,
+            review_items,
+            review_schedule,
+            ranges_screen: ranges_screen,
+            study_screen: study_screen::StudyScreen::new(None),
+            statistics_screen: statistics_screen::StatisticsScreen::default(),
+            toolbar: toolbar::ToolBar::default(),
+            current_screen: ScreenType::default(),
+            confirmation: None,
+            settings,
+            attempt: None,
+            equity_answers: HashMap::new(),
+            pending_equity_answers: HashMap::new(),
+            equity_answers_stale: false,
+        };
+
+        // select first review item for study
+        range_trainer.show_next_question();
 
-    fn view(&mut self) -> Element<Self::Message> {
+        // move progress saved under the old question keys over to question
+        // IDs. Equity questions are only found by their answers, so theirs
+        // moves over once the equities have been worked out.
+        if range_trainer.review_items.has_legacy() {
+            if let Err(e) = range_trainer.rebuild_review_items() {
+                errors.push(format!("Saving review items failed: {}", e));
+            }
+        }
+        if !errors.is_empty() {
+            range_trainer.ranges_screen.update(RangesMessage::FileStatus(errors.join(" ")));
+        }
+
+        let command = range_trainer.update_equity_answers();
+        (range_trainer, command)
+    }
+
+    fn title(&self) -> String {
+        String::from("Range Trainer")
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        shortcuts::shortcuts().map(Message::Shortcut)
+    }
+
+    fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
+        // while a confirmation is up only its answer and work finishing in
+        // the background get through
+        if self.confirmation.is_some() {
+            match message {
+                Message::ResolveConfirmation(_)
+                | Message::RangesScreen(RangesMessage::RangeEquityComputed(..))
+                | Message::RangesScreen(RangesMessage::HandOrderComputed(..))
+                | Message::EquityAnswersComputed(..) => {}
+                _ => return Command::none(),
+            }
+        }
+        match self.confirmation_for(&message) {
+            Some(confirmation) => {
+                self.confirmation = Some(confirmation);
+                Command::none()
+            }
+            None => {
+                let command = self.perform(message);
+                if self.equity_answers_stale {
+                    Command::batch(vec![command, self.update_equity_answers()])
+                } else {
+                    command
+                }
+            }
+        }
+    }
+
+    fn view(&mut self) -> Element<'_, Self::Message> {
+        // a confirmation takes over the window so nothing behind it can be
+        // clicked until it is answered
+        if let Some(confirmation) = &mut self.confirmation {
+            return Container::new(confirmation.view().max_width(600))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y()
+                .padding(8)
+                .into();
+        }
         Column::new()
             .padding(8)
             .spacing(8)
             .align_items(Align::Center)
             .push(self.toolbar.view())
             .push(match &self.current_screen {
-                ScreenType::Study => self.study_screen.view(),
+                ScreenType::Study => self.study_screen.view(self.settings.scheduler),
                 ScreenType::Ranges => Container::new(self.ranges_screen.view()),
+                ScreenType::Statistics => self.statistics_screen.view(),
             })
             .into()
     }
 }
+
+impl Drop for RangeTrainer {
+    // iced closes the window without asking the application, so there is no
+    // chance to confirm; unsaved edits are kept rather than lost
+    fn drop(&mut self) {
+        let active_range = match &self.ranges_screen.active_range {
+            Some(active_range) if active_range.is_dirty() => active_range.clone(),
+            _ => return,
+        };
+        if let Some(r) = self.ranges.get_mut(active_range.id) {
+            *r = active_range.range;
+            if let Err(e) = fileio::save_ranges(&self.ranges) {
+                eprintln!("Saving ranges failed: {}", e);
+            }
+            if let Err(e) = self.rebuild_review_items() {
+                eprintln!("Saving review items failed: {}", e);
+            }
+        }
+    }
+}
@@ -1,14 +1,23 @@
+use std::collections::HashSet;
 use std::default::Default;
 
 use crate::card::Card;
+use crate::equity::RangeEquity;
 use crate::hand::Hand;
 use crate::hand::Suit;
 use crate::messages::{Message, RangesMessage};
-use crate::range::Range;
+use crate::metadata::{self, ActionContext, GameType, Position, RangeMetadata};
+use crate::notation;
+use crate::ordering::{Chen, HandOrdering, OrderingKind};
+use crate::range::{self, Range, RangeOperation};
+use crate::range_file::RangeFormat;
+use crate::review::item::PokerAction;
+use crate::strategy::Strategy;
 use crate::styles;
 
 use iced::{
-    button, scrollable, text_input, Button, Column, Container, Row, Scrollable, Text, TextInput,
+    button, scrollable, slider, text_input, Button, Column, Container, Row, Scrollable, Slider,
+    Space, Text, TextInput,
 };
 use iced_native::{
     input, layout, Align, Background, Clipboard, Color, Element, Event, Font, Hasher,
@@ -18,28 +27,54 @@ use iced_native::{
 use iced_wgpu::{Defaults, Primitive, Renderer};
 
 pub struct SelectRangeButton {
-    range_name: String,
+    range: Range,
     range_id: usize,
     button_state: button::State,
 }
 
 impl SelectRangeButton {
-    fn new(range_id: usize, range_name: String) -> Self {
+    fn new(range_id: usize, range: Range) -> Self {
         Self {
-            range_name,
+            range,
             range_id,
             button_state: button::State::new(),
         }
     }
 
-    fn view(&mut self) -> Button<Message> {
+    fn view(&mut self, selected: bool) -> Button<'_, Message> {
         Button::new(
             &mut self.button_state,
-            Text::new(self.range_name.to_string()).horizontal_alignment(HorizontalAlignment::Left),
+            Text::new(self.range.name.to_string()).horizontal_alignment(HorizontalAlignment::Left),
         )
         .on_press(Message::RangeSelected(self.range_id))
         .width(Length::Fill)
-        .style(styles::Button::RangeList { selected: false })
+        .style(styles::Button::RangeList { selected })
+    }
+}
+
+pub struct SelectStrategyButton {
+    strategy_name: String,
+    strategy_id: usize,
+    button_state: button::State,
+}
+
+impl SelectStrategyButton {
+    fn new(strategy_id: usize, strategy_name: String) -> Self {
+        Self {
+            strategy_name,
+            strategy_id,
+            button_state: button::State::new(),
+        }
+    }
+
+    fn view(&mut self, selected: bool) -> Button<'_, Message> {
+        Button::new(
+            &mut self.button_state,
+            Text::new(self.strategy_name.to_string()).horizontal_alignment(HorizontalAlignment::Left),
+        )
+        .on_press(Message::StrategySelected(self.strategy_id))
+        .width(Length::Fill)
+        .style(styles::Button::RangeList { selected })
     }
 }
 
@@ -47,9 +82,33 @@ impl SelectRangeButton {
 pub struct ActiveRange {
     pub id: usize,
     pub range: Range,
+    /// The range as it was last saved, so undoing back to it is not an
+    /// unsaved change.
+    saved: Range,
     dirty: bool,
+    undo_stack: Vec<Range>,
+    redo_stack: Vec<Range>,
+    last_edit: Option<Edit>,
+}
+
+/// Kinds of change to the active range. Typing into the same field several
+/// times in a row is undone in one step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    Hands,
+    Name,
+    Notation,
+    Metadata,
+    Folder,
+    Tags,
+    TopPercent,
 }
 
+const MAX_HISTORY: usize = 100;
+
+/// How far each level of folder nesting is indented in the range list.
+const FOLDER_INDENT: u16 = 16;
+
 impl From<ActiveRange> for Range {
     fn from(active_range: ActiveRange) -> Range {
         active_range.range.clone()
@@ -60,12 +119,94 @@ impl ActiveRange {
     pub fn new(id: usize, range: Range) -> Self {
         Self {
             id,
+            saved: range.clone(),
             range,
             dirty: false,
+            undo_stack: vec![],
+            redo_stack: vec![],
+            last_edit: None,
+        }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Remembers the range as it is before an edit so it can be undone.
+    fn record(&mut self, edit: Edit) {
+        if edit == Edit::Hands || self.last_edit != Some(edit) {
+            self.undo_stack.push(self.range.clone());
+            if self.undo_stack.len() > MAX_HISTORY {
+                self.undo_stack.remove(0);
+            }
+        }
+        self.redo_stack.clear();
+        self.last_edit = Some(edit);
+        self.dirty = true;
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(range) = self.undo_stack.pop() {
+            self.redo_stack.push(std::mem::replace(&mut self.range, range));
+            self.last_edit = None;
+            self.dirty = self.range != self.saved;
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(range) = self.redo_stack.pop() {
+            self.undo_stack.push(std::mem::replace(&mut self.range, range));
+            self.last_edit = None;
+            self.dirty = self.range != self.saved;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveStrategy {
+    pub id: usize,
+    pub strategy: Strategy,
+    dirty: bool,
+}
+
+impl ActiveStrategy {
+    pub fn new(id: usize, strategy: Strategy) -> Self {
+        Self {
+            id,
+            strategy,
+            dirty: false,
         }
     }
 }
 
+/// The action painted onto a strategy when a hand is clicked.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StrategyBrush {
+    #[default]
+    Raise,
+    Call,
+    Fold,
+}
+
+/// Mouse input on a cell of the hand matrix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatrixInput {
+    Press(Hand),
+    ErasePress(Hand),
+    DragOver(Hand),
+    Release,
+}
+
+/// A press and drag across the matrix. Every hand dragged over is set to
+/// what the first hand became, or cleared when erasing.
+#[derive(Debug, Clone)]
+pub struct DragSession {
+    anchor: Hand,
+    erase: bool,
+    weight: f32,
+    touched: HashSet<Hand>,
+}
+
 #[derive(Default)]
 pub struct RangesScreen {
     pub active_range: Option<ActiveRange>,
@@ -73,53 +214,301 @@ pub struct RangesScreen {
     pub save_current_range_button: button::State,
     pub copy_current_range_button: button::State,
     pub delete_current_range_button: button::State,
+    pub undo_button: button::State,
+    pub table_size_button: button::State,
+    pub position_button: button::State,
+    pub action_context_button: button::State,
+    pub game_type_button: button::State,
+    pub stack_depth_state: text_input::State,
+    pub redo_button: button::State,
     pub ranges_scrollable: scrollable::State,
     pub select_range_buttons: Vec<SelectRangeButton>,
+    pub search_state: text_input::State,
+    pub search_value: String,
+    pub folder_state: text_input::State,
+    pub tags_state: text_input::State,
+    pub tags_value: String,
+    pub move_up_button: button::State,
+    pub move_down_button: button::State,
     pub new_range_button: button::State,
+    /// The ID of the range picked with the vs button.
+    pub compare_range: Option<String>,
+    pub compare_range_button: button::State,
+    pub compute_equity_button: button::State,
+    pub heat_map: Option<RangeEquity>,
+    pub union_button: button::State,
+    pub intersect_button: button::State,
+    pub subtract_button: button::State,
+    pub invert_button: button::State,
+    pub compare_button: button::State,
+    pub comparison_range: Option<Range>,
+    pub notation_state: text_input::State,
+    pub notation_value: String,
+    pub notation_error: Option<String>,
+    pub brush_weight: f32,
+    pub brush_weight_slider: slider::State,
+    pub active_strategy: Option<ActiveStrategy>,
+    pub select_strategy_buttons: Vec<SelectStrategyButton>,
+    pub strategies_scrollable: scrollable::State,
+    pub new_strategy_button: button::State,
+    pub current_strategy_name_state: text_input::State,
+    pub save_current_strategy_button: button::State,
+    pub delete_current_strategy_button: button::State,
+    pub strategy_brush: StrategyBrush,
+    pub raise_brush_button: button::State,
+    pub call_brush_button: button::State,
+    pub fold_brush_button: button::State,
+    pub raise_size_state: text_input::State,
+    pub raise_size_value: String,
+    pub drag: Option<DragSession>,
+    pub file_path_state: text_input::State,
+    pub file_path_value: String,
+    pub file_format: RangeFormat,
+    pub file_format_button: button::State,
+    pub import_button: button::State,
+    pub export_range_button: button::State,
+    pub export_library_button: button::State,
+    pub export_chart_button: button::State,
+    pub hand_ordering: OrderingKind,
+    pub hand_ordering_label: String,
+    pub hand_ordering_button: button::State,
+    /// Hands from strongest to weakest by `hand_ordering`, or `None` while
+    /// an equity ordering is being worked out.
+    pub hand_order: Option<Vec<Hand>>,
+    pub top_percent: f32,
+    pub top_percent_slider: slider::State,
+    pub file_status: String,
 }
 
 impl RangesScreen {
     pub fn new() -> Self {
         Self {
+            brush_weight: 1.0,
+            raise_size_value: String::from("2.5"),
+            hand_ordering_label: OrderingKind::Chen.to_string(),
+            hand_order: Some(Chen.order()),
             ..Default::default()
         }
     }
 
     pub fn set_range(&mut self, id: usize, range: Range) {
         self.active_range = Some(ActiveRange::new(id, range));
+        self.active_strategy = None;
+        self.heat_map = None;
+        self.comparison_range = None;
+        self.refresh_notation();
+        self.refresh_tags();
     }
 
-    pub fn view(&mut self) -> Row<Message> {
-        let range_matrix = if let Some(active_range) = &mut self.active_range {
-            Card::iterator().rev().enumerate().fold(
-                Column::new().spacing(4),
-                |column, (row_idx, row_card)| {
-                    column.push(Card::iterator().rev().enumerate().fold(
-                        Row::new().height(Length::Fill).spacing(4),
-                        |row, (col_idx, col_card)| {
-                            let suited = {
-                                if col_idx > row_idx {
-                                    Suit::Suited
-                                } else {
-                                    Suit::Off
-                                }
-                            };
-                            let first = Card::max(*row_card, *col_card);
-                            let second = Card::min(*row_card, *col_card);
-                            let hand = Hand {
-                                first,
-                                second,
-                                suited,
-                            };
-                            row.push(HandToggle::<Message>::new(
-                                active_range.range.contains(&hand),
-                                hand,
-                                |h| Message::RangesScreen(RangesMessage::ToggleHand(h)),
-                            ))
-                        },
-                    ))
-                },
-            )
+    pub fn set_strategy(&mut self, id: usize, strategy: Strategy) {
+        self.active_strategy = Some(ActiveStrategy::new(id, strategy));
+        self.active_range = None;
+        self.heat_map = None;
+        self.comparison_range = None;
+        self.refresh_notation();
+        self.refresh_tags();
+    }
+
+    /// Toggles `hand` with the current brush and returns the weight it ends
+    /// up with, which the rest of a drag paints.
+    fn toggle_hand(&mut self, hand: Hand) -> f32 {
+        let action = self.brush_action();
+        let weight = if let Some(active_range) = &mut self.active_range {
+            active_range.record(Edit::Hands);
+            active_range.range.toggle_weighted(hand, self.brush_weight);
+            active_range.range.weight(&hand)
+        } else if let Some(active_strategy) = &mut self.active_strategy {
+            active_strategy.strategy.paint(hand, action.clone(), self.brush_weight);
+            active_strategy.dirty = true;
+            active_strategy.strategy.frequency(&hand, &action)
+        } else {
+            0.0
+        };
+        self.refresh_notation();
+        weight
+    }
+
+    /// Sets `hand` to `weight` with the current brush, or clears it.
+    fn paint_hand(&mut self, hand: Hand, erase: bool, weight: f32) {
+        let action = self.brush_action();
+        if let Some(active_range) = &mut self.active_range {
+            active_range.range.set_weight(hand, if erase { 0.0 } else { weight });
+            active_range.dirty = true;
+        } else if let Some(active_strategy) = &mut self.active_strategy {
+            if erase {
+                active_strategy.strategy.set_frequency(hand, PokerAction::Fold, 1.0);
+            } else {
+                active_strategy.strategy.set_frequency(hand, action, weight);
+            }
+            active_strategy.dirty = true;
+        }
+        self.refresh_notation();
+    }
+
+    fn start_drag(&mut self, anchor: Hand, erase: bool, weight: f32) {
+        let mut touched = HashSet::new();
+        touched.insert(anchor);
+        self.drag = Some(DragSession {
+            anchor,
+            erase,
+            weight,
+            touched,
+        });
+    }
+
+    fn brush_action(&self) -> PokerAction {
+        match self.strategy_brush {
+            StrategyBrush::Raise => PokerAction::Raise(
+                self.raise_size_value
+                    .trim()
+                    .parse::<f32>()
+                    .map(|size| size.to_string())
+                    .unwrap_or_else(|_| String::from("2.5")),
+            ),
+            StrategyBrush::Call => PokerAction::Call,
+            StrategyBrush::Fold => PokerAction::Fold,
+        }
+    }
+
+    fn refresh_notation(&mut self) {
+        self.notation_value = match &self.active_range {
+            Some(active_range) => active_range.range.notation(),
+            None => String::new(),
+        };
+        self.notation_error = None;
+    }
+
+    fn refresh_tags(&mut self) {
+        self.tags_value = match &self.active_range {
+            Some(active_range) => active_range.range.tags.join(", "),
+            None => String::new(),
+        };
+    }
+
+    fn compare_range_name(&self) -> Option<&str> {
+        self.compare_range
+            .as_ref()
+            .and_then(|id| self.select_range_buttons.iter().find(|b| &b.range.id == id))
+            .map(|b| b.range.name.as_str())
+    }
+
+    pub fn view(&mut self) -> Row<'_, Message> {
+        let range_info = if let Some(active_range) = &self.active_range {
+            let compare_label = match self.compare_range_name() {
+                Some(name) => format!("vs: {}", name),
+                None => String::from("vs: none"),
+            };
+            let mut compute_equity_button =
+                Button::new(&mut self.compute_equity_button, Text::new("Equity"))
+                    .style(styles::Button::Basic);
+            if let Some(compare_range) = &self.compare_range {
+                compute_equity_button = compute_equity_button.on_press(
+                    Message::ComputeRangeEquity(active_range.clone(), compare_range.clone()),
+                );
+            }
+            let mut compare_button = match &self.comparison_range {
+                Some(_) => Button::new(&mut self.compare_button, Text::new("Close compare"))
+                    .on_press(Message::RangesScreen(RangesMessage::CloseComparison)),
+                None => Button::new(&mut self.compare_button, Text::new("Compare")),
+            }
+            .style(styles::Button::Basic);
+            if let (Some(compare_range), None) = (&self.compare_range, &self.comparison_range) {
+                compare_button = compare_button.on_press(Message::CompareRanges(compare_range.clone()));
+            }
+            let summary = match (&self.comparison_range, &self.heat_map) {
+                (Some(other), _) => {
+                    let comparison = active_range.range.compare(other);
+                    format!(
+                        "Only this: {:.0}, both: {:.0}, only {}: {:.0} combos",
+                        comparison.only_first.combo_count(),
+                        comparison.both.combo_count(),
+                        other.name,
+                        comparison.only_second.combo_count()
+                    )
+                }
+                (None, Some(heat_map)) => {
+                    let overall = heat_map.overall_of(active_range.range.hands.keys());
+                    format!(
+                        "Equity {:.1}% (win {:.1}%, tie {:.1}%)",
+                        overall.equity_percent(),
+                        overall.win_percent(),
+                        overall.tie_percent()
+                    )
+                }
+                (None, None) => String::from(""),
+            };
+            Row::new()
+                .spacing(8)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.compare_range_button, Text::new(compare_label))
+                        .on_press(Message::RangesScreen(RangesMessage::CycleCompareRange))
+                        .style(styles::Button::Basic),
+                )
+                .push(compute_equity_button)
+                .push(compare_button)
+                .push(Text::new(summary))
+        } else {
+            Row::new()
+        };
+
+        let range_operations = if let Some(active_range) = &self.active_range {
+            let compare_range = &self.compare_range;
+            let operation_button = |state, label, operation| {
+                let button = Button::new(state, Text::new(label)).style(styles::Button::Basic);
+                match compare_range {
+                    Some(id) => button.on_press(Message::CombineRanges(active_range.clone(), id.clone(), operation)),
+                    None => button,
+                }
+            };
+            Row::new()
+                .spacing(8)
+                .align_items(Align::Center)
+                .push(operation_button(&mut self.union_button, "Add", RangeOperation::Union))
+                .push(operation_button(&mut self.intersect_button, "Intersect", RangeOperation::Intersection))
+                .push(operation_button(&mut self.subtract_button, "Subtract", RangeOperation::Difference))
+                .push(
+                    Button::new(&mut self.invert_button, Text::new("Invert"))
+                        .on_press(Message::RangesScreen(RangesMessage::InvertRange))
+                        .style(styles::Button::Basic),
+                )
+        } else {
+            Row::new()
+        };
+
+        let heat_map = &self.heat_map;
+        let drag = self.drag.as_ref();
+        let range_matrix = if let (Some(active_range), Some(other)) = (&self.active_range, &self.comparison_range) {
+            let comparison = active_range.range.compare(other);
+            hand_matrix(drag, |hand| {
+                let split = vec![
+                    (ONLY_FIRST_COLOR, comparison.only_first.weight(&hand)),
+                    (BOTH_COLOR, comparison.both.weight(&hand)),
+                    (ONLY_SECOND_COLOR, comparison.only_second.weight(&hand)),
+                ];
+                HandToggle::<Message>::new(0.0, hand, matrix_message).split(split)
+            })
+        } else if let Some(active_range) = &self.active_range {
+            hand_matrix(drag, |hand| {
+                let weight = active_range.range.weight(&hand);
+                let heat = heat_map
+                    .as_ref()
+                    .filter(|_| weight > 0.0)
+                    .and_then(|h| h.per_hand.get(&hand))
+                    .map(|e| e.equity_percent());
+                HandToggle::<Message>::new(weight, hand, matrix_message).heat(heat)
+            })
+        } else if let Some(active_strategy) = &self.active_strategy {
+            hand_matrix(drag, |hand| {
+                let split = active_strategy
+                    .strategy
+                    .distribution(&hand)
+                    .iter()
+                    .filter(|(action, _)| *action != PokerAction::Fold)
+                    .map(|(action, frequency)| (action_color(action), *frequency))
+                    .collect();
+                HandToggle::<Message>::new(0.0, hand, matrix_message).split(split)
+            })
         } else {
             Column::new()
                 .height(Length::Fill)
@@ -134,15 +523,72 @@ impl RangesScreen {
                 )
         };
 
-        let range_list = Container::new(
-            self.select_range_buttons.iter_mut().fold(
-                Scrollable::new(&mut self.ranges_scrollable)
-                    .spacing(8),
-                |s, b| s.push(b.view()),
+        // ranges matching the search, grouped under a heading for each folder
+        let active_range_id = self.active_range.as_ref().map(|r| r.id);
+        let search = &self.search_value;
+        let has_ranges = !self.select_range_buttons.is_empty();
+        let mut range_buttons = self
+            .select_range_buttons
+            .iter_mut()
+            .filter(|b| b.range.matches(search))
+            .collect::<Vec<&mut SelectRangeButton>>();
+        range_buttons.sort_by(|a, b| a.range.folder_path().cmp(&b.range.folder_path()));
+        let mut ranges_scrollable = Scrollable::new(&mut self.ranges_scrollable)
+            .spacing(8)
+            .push(TextInput::new(
+                &mut self.search_state,
+                "Search ranges",
+                search,
+                |s| Message::RangesScreen(RangesMessage::SearchChanged(s)),
+            ));
+        if range_buttons.is_empty() && has_ranges {
+            ranges_scrollable = ranges_scrollable.push(Text::new("No matching ranges."));
+        }
+        let mut open_folders: Vec<String> = vec![];
+        for button in range_buttons {
+            let path = button
+                .range
+                .folder_path()
+                .iter()
+                .map(|folder| folder.to_string())
+                .collect::<Vec<String>>();
+            let shared = open_folders
+                .iter()
+                .zip(path.iter())
+                .take_while(|(open, folder)| open == folder)
+                .count();
+            for (depth, folder) in path.iter().enumerate().skip(shared) {
+                ranges_scrollable = ranges_scrollable.push(
+                    Row::new()
+                        .push(Space::with_width(Length::Units(FOLDER_INDENT * depth as u16)))
+                        .push(Text::new(folder.to_string())),
+                );
+            }
+            let selected = active_range_id == Some(button.range_id);
+            ranges_scrollable = ranges_scrollable.push(
+                Row::new()
+                    .push(Space::with_width(Length::Units(FOLDER_INDENT * path.len() as u16)))
+                    .push(button.view(selected)),
+            );
+            open_folders = path;
+        }
+        let range_list = Container::new(ranges_scrollable)
+            .style(styles::Container::Basic)
+            .height(Length::FillPortion(6))
+            .padding(24);
+
+        let active_strategy_id = self.active_strategy.as_ref().map(|s| s.id);
+        let strategy_list = Container::new(
+            self.select_strategy_buttons.iter_mut().fold(
+                Scrollable::new(&mut self.strategies_scrollable).spacing(8),
+                |s, b| {
+                    let selected = active_strategy_id == Some(b.strategy_id);
+                    s.push(b.view(selected))
+                },
             ),
         )
         .style(styles::Container::Basic)
-        .height(Length::FillPortion(9))
+        .height(Length::FillPortion(3))
         .padding(24);
 
         let range_controls = if let Some(active_range) = &self.active_range {
@@ -155,6 +601,11 @@ impl RangesScreen {
                     |s| Message::RangesScreen(RangesMessage::RangeNameChanged(s)),
                 ))
                 .push(Text::new(if active_range.dirty { "*" } else { "" }))
+                .push(Text::new(format!(
+                    "{:.0} combos ({:.1}%)",
+                    active_range.range.combo_count(),
+                    active_range.range.percent()
+                )))
                 .push(
                     Button::new(&mut self.save_current_range_button, Text::new("Save"))
                         .on_press(Message::SaveRange(Some(active_range.clone())))
@@ -170,10 +621,219 @@ impl RangesScreen {
                         .on_press(Message::DeleteRange(Some(active_range.clone())))
                         .style(styles::Button::Basic),
                 )
+                .push(
+                    Button::new(&mut self.undo_button, Text::new("Undo"))
+                        .on_press(Message::RangesScreen(RangesMessage::Undo))
+                        .style(styles::Button::Basic),
+                )
+                .push(
+                    Button::new(&mut self.redo_button, Text::new("Redo"))
+                        .on_press(Message::RangesScreen(RangesMessage::Redo))
+                        .style(styles::Button::Basic),
+                )
+        } else if let Some(active_strategy) = &self.active_strategy {
+            Row::new()
+                .spacing(8)
+                .push(TextInput::new(
+                    &mut self.current_strategy_name_state,
+                    "Strategy name",
+                    &active_strategy.strategy.name,
+                    |s| Message::RangesScreen(RangesMessage::StrategyNameChanged(s)),
+                ))
+                .push(Text::new(if active_strategy.dirty { "*" } else { "" }))
+                .push(
+                    Button::new(&mut self.save_current_strategy_button, Text::new("Save"))
+                        .on_press(Message::SaveStrategy(Some(active_strategy.clone())))
+                        .style(styles::Button::Basic),
+                )
+                .push(
+                    Button::new(&mut self.delete_current_strategy_button, Text::new("Delete"))
+                        .on_press(Message::DeleteStrategy(Some(active_strategy.clone())))
+                        .style(styles::Button::Basic),
+                )
         } else {
             Row::new()
         };
 
+        let metadata_controls = if let Some(active_range) = &self.active_range {
+            let metadata = &active_range.range.metadata;
+            let changed = |change: &dyn Fn(&mut RangeMetadata)| {
+                let mut metadata = metadata.clone();
+                change(&mut metadata);
+                Message::RangesScreen(RangesMessage::MetadataChanged(metadata))
+            };
+            let stack_metadata = metadata.clone();
+            Row::new()
+                .spacing(8)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(
+                        &mut self.table_size_button,
+                        Text::new(metadata.table_size.map(metadata::table_size_name).unwrap_or_else(|| String::from("Table: any"))),
+                    )
+                    .on_press(changed(&|m| m.table_size = cycle(metadata::TABLE_SIZES.iter(), &m.table_size)))
+                    .style(styles::Button::Basic),
+                )
+                .push(
+                    Button::new(
+                        &mut self.position_button,
+                        Text::new(metadata.position.map(|p| p.to_string()).unwrap_or_else(|| String::from("Position: any"))),
+                    )
+                    .on_press(changed(&|m| m.position = cycle(Position::iterator(), &m.position)))
+                    .style(styles::Button::Basic),
+                )
+                .push(
+                    Button::new(
+                        &mut self.action_context_button,
+                        Text::new(metadata.action.map(|a| a.to_string()).unwrap_or_else(|| String::from("Action: any"))),
+                    )
+                    .on_press(changed(&|m| m.action = cycle(ActionContext::iterator(), &m.action)))
+                    .style(styles::Button::Basic),
+                )
+                .push(
+                    TextInput::new(
+                        &mut self.stack_depth_state,
+                        "Stack (BB)",
+                        &metadata.stack_depth.map(|s| s.to_string()).unwrap_or_default(),
+                        move |s| {
+                            let mut metadata = stack_metadata.clone();
+                            if s.trim().is_empty() {
+                                metadata.stack_depth = None;
+                            } else if let Ok(stack_depth) = s.trim().parse::<u32>() {
+                                metadata.stack_depth = Some(stack_depth);
+                            }
+                            Message::RangesScreen(RangesMessage::MetadataChanged(metadata))
+                        },
+                    )
+                    .width(Length::Units(96)),
+                )
+                .push(
+                    Button::new(
+                        &mut self.game_type_button,
+                        Text::new(metadata.game_type.map(|g| g.to_string()).unwrap_or_else(|| String::from("Game: any"))),
+                    )
+                    .on_press(changed(&|m| m.game_type = cycle(GameType::iterator(), &m.game_type)))
+                    .style(styles::Button::Basic),
+                )
+        } else {
+            Row::new()
+        };
+
+        let library_controls = if let Some(active_range) = &self.active_range {
+            Row::new()
+                .spacing(8)
+                .align_items(Align::Center)
+                .push(TextInput::new(
+                    &mut self.folder_state,
+                    "Folder, e.g. 6-max/UTG",
+                    &active_range.range.folder,
+                    |s| Message::RangesScreen(RangesMessage::FolderChanged(s)),
+                ))
+                .push(TextInput::new(
+                    &mut self.tags_state,
+                    "Tags, comma separated",
+                    &self.tags_value,
+                    |s| Message::RangesScreen(RangesMessage::TagsChanged(s)),
+                ))
+                .push(
+                    Button::new(&mut self.move_up_button, Text::new("Move up"))
+                        .on_press(Message::MoveRangeUp(active_range.id))
+                        .style(styles::Button::Basic),
+                )
+                .push(
+                    Button::new(&mut self.move_down_button, Text::new("Move down"))
+                        .on_press(Message::MoveRangeDown(active_range.id))
+                        .style(styles::Button::Basic),
+                )
+        } else {
+            Row::new()
+        };
+
+        let percent_controls = if self.active_range.is_some() {
+            // equity against a range needs one picked with the vs button
+            let next_ordering = match cycle(OrderingKind::iterator(), &Some(self.hand_ordering)) {
+                Some(OrderingKind::EquityVsRange) if self.compare_range.is_none() => OrderingKind::Chen,
+                next => next.unwrap_or_default(),
+            };
+            Row::new()
+                .spacing(8)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(
+                        &mut self.hand_ordering_button,
+                        Text::new(format!("Order: {}", self.hand_ordering_label)),
+                    )
+                    .on_press(Message::SelectHandOrdering(next_ordering))
+                    .style(styles::Button::Basic),
+                )
+                .push(Text::new(match self.hand_order {
+                    Some(_) => format!("Top {:.0}%", self.top_percent),
+                    None => String::from("Working out equities..."),
+                }))
+                .push(
+                    Slider::new(
+                        &mut self.top_percent_slider,
+                        0.0..=100.0,
+                        self.top_percent,
+                        |p| Message::RangesScreen(RangesMessage::TopPercentChanged(p)),
+                    )
+                    .width(Length::Units(240)),
+                )
+        } else {
+            Row::new()
+        };
+
+        let notation_controls = if self.active_range.is_some() {
+            Row::new()
+                .spacing(8)
+                .align_items(Align::Center)
+                .push(TextInput::new(
+                    &mut self.notation_state,
+                    "Range notation, e.g. TT+, AQs+, AKo",
+                    &self.notation_value,
+                    |s| Message::RangesScreen(RangesMessage::NotationChanged(s)),
+                ))
+                .push(Text::new(match &self.notation_error {
+                    Some(token) => format!("Invalid notation: {}", token),
+                    None => String::new(),
+                }))
+        } else {
+            Row::new()
+        };
+
+        let mut brush_controls = Row::new().spacing(8).align_items(Align::Center);
+        if self.active_strategy.is_some() {
+            let brush = self.strategy_brush;
+            let brush_button = |state, label, value| {
+                Button::new(state, Text::new(label))
+                    .on_press(Message::RangesScreen(RangesMessage::StrategyBrushSelected(value)))
+                    .style(styles::Button::RangeList { selected: brush == value })
+            };
+            brush_controls = brush_controls
+                .push(brush_button(&mut self.raise_brush_button, "Raise", StrategyBrush::Raise))
+                .push(
+                    TextInput::new(&mut self.raise_size_state, "BB", &self.raise_size_value, |s| {
+                        Message::RangesScreen(RangesMessage::RaiseSizeChanged(s))
+                    })
+                    .width(Length::Units(48)),
+                )
+                .push(brush_button(&mut self.call_brush_button, "Call", StrategyBrush::Call))
+                .push(brush_button(&mut self.fold_brush_button, "Fold", StrategyBrush::Fold));
+        }
+        if self.active_range.is_some() || self.active_strategy.is_some() {
+            brush_controls = brush_controls
+                .push(Text::new(format!("Weight: {:.0}%", self.brush_weight * 100.0)))
+                .push(
+                    Slider::new(
+                        &mut self.brush_weight_slider,
+                        0.05..=1.0,
+                        self.brush_weight,
+                        |w| Message::RangesScreen(RangesMessage::BrushWeightChanged(w)),
+                    )
+                    .width(Length::Units(120)),
+                );
+        }
+
         let new_range_button = Button::new(
             &mut self.new_range_button,
             Text::new("New Range")
@@ -184,6 +844,65 @@ impl RangesScreen {
         .on_press(Message::CreateNewRange)
         .style(styles::Button::Basic);
 
+        let new_strategy_button = Button::new(
+            &mut self.new_strategy_button,
+            Text::new("New Strategy")
+                .horizontal_alignment(HorizontalAlignment::Center)
+                .vertical_alignment(VerticalAlignment::Center),
+        )
+        .height(Length::FillPortion(1))
+        .on_press(Message::CreateNewStrategy)
+        .style(styles::Button::Basic);
+
+        let file_path = self.file_path_value.trim().to_string();
+        let file_format = self.file_format;
+        let file_button = |state, label, message: Option<Message>| {
+            let button = Button::new(state, Text::new(label)).style(styles::Button::Basic);
+            match message {
+                Some(message) if !file_path.is_empty() => button.on_press(message),
+                _ => button,
+            }
+        };
+        let export_range = self
+            .active_range
+            .as_ref()
+            .map(|active_range| Message::ExportRange(active_range.clone(), file_path.clone(), file_format));
+        let export_chart = if self.active_range.is_some() || self.active_strategy.is_some() {
+            Some(Message::ExportChart(file_path.clone()))
+        } else {
+            None
+        };
+        let file_controls = Column::new()
+            .spacing(4)
+            .push(TextInput::new(
+                &mut self.file_path_state,
+                "File or folder path",
+                &self.file_path_value,
+                |s| Message::RangesScreen(RangesMessage::FilePathChanged(s)),
+            ))
+            .push(
+                Row::new()
+                    .spacing(4)
+                    .push(
+                        Button::new(&mut self.file_format_button, Text::new(format!("Format: {}", file_format)))
+                            .on_press(Message::RangesScreen(RangesMessage::CycleFileFormat))
+                            .style(styles::Button::Basic),
+                    )
+                    .push(file_button(&mut self.import_button, "Import", Some(Message::ImportRanges(file_path.clone()))))
+                    .push(file_button(&mut self.export_range_button, "Export range", export_range))
+                    .push(file_button(
+                        &mut self.export_library_button,
+                        "Export library",
+                        Some(Message::ExportLibrary(file_path.clone(), file_format)),
+                    )),
+            )
+            .push(file_button(
+                &mut self.export_chart_button,
+                "Export chart (SVG)",
+                export_chart,
+            ))
+            .push(Text::new(self.file_status.to_string()));
+
         Row::new() // master containewr
             .spacing(8)
             .push(
@@ -191,8 +910,14 @@ impl RangesScreen {
                     .spacing(8)
                     .align_items(Align::Center)
                     .width(Length::FillPortion(2))
-                    .push(Row::new()) // range info
+                    .push(range_info)
+                    .push(range_operations)
                     .push(range_controls)
+                    .push(metadata_controls)
+                    .push(library_controls)
+                    .push(notation_controls)
+                    .push(percent_controls)
+                    .push(brush_controls)
                     .push(range_matrix),
             )
             .push(
@@ -200,32 +925,219 @@ impl RangesScreen {
                     .width(Length::FillPortion(1))
                     .push(range_list) // list of ranges
                     .push(new_range_button) // new range button
+                    .push(strategy_list) // list of strategies
+                    .push(new_strategy_button)
+                    .push(file_controls)
                     .spacing(4),
             )
     }
 
     pub fn update(&mut self, message: RangesMessage) {
         match message {
-            RangesMessage::ToggleHand(hand) => {
+            RangesMessage::MatrixInput(MatrixInput::Press(hand)) => {
+                let weight = self.toggle_hand(hand);
+                self.start_drag(hand, false, weight);
+            }
+            RangesMessage::MatrixInput(MatrixInput::ErasePress(hand)) => {
                 if let Some(active_range) = &mut self.active_range {
-                    active_range.range.toggle(hand);
-                    active_range.dirty = true;
+                    active_range.record(Edit::Hands);
                 }
+                self.paint_hand(hand, true, 0.0);
+                self.start_drag(hand, true, 0.0);
+            }
+            RangesMessage::MatrixInput(MatrixInput::DragOver(hand)) => {
+                if let Some(drag) = &mut self.drag {
+                    if drag.touched.insert(hand) {
+                        let (erase, weight) = (drag.erase, drag.weight);
+                        self.paint_hand(hand, erase, weight);
+                    }
+                }
+            }
+            RangesMessage::MatrixInput(MatrixInput::Release) => {
+                self.drag = None;
+            }
+            RangesMessage::NotationChanged(value) => {
+                if let Some(active_range) = &mut self.active_range {
+                    match notation::parse(&value) {
+                        Ok(hands) => {
+                            if hands != active_range.range.hands {
+                                active_range.record(Edit::Notation);
+                                active_range.range.hands = hands;
+                            }
+                            self.notation_error = None;
+                        }
+                        Err(notation::ParseNotationError(token)) => {
+                            self.notation_error = Some(token)
+                        }
+                    }
+                }
+                self.notation_value = value;
+            }
+            RangesMessage::BrushWeightChanged(weight) => {
+                self.brush_weight = (weight * 20.0).round() / 20.0;
             }
             RangesMessage::UpdateSelectRangeButtons(ranges) => {
                 self.select_range_buttons = ranges
                     .iter()
                     .enumerate()
-                    .map(|(i, r)| SelectRangeButton::new(i, r.name.to_string()))
-                    .collect()
+                    .map(|(i, r)| SelectRangeButton::new(i, r.clone()))
+                    .collect();
+                if matches!(&self.compare_range, Some(id) if range::find(&ranges, id).is_none()) {
+                    self.compare_range = None;
+                }
+            }
+            RangesMessage::ComparisonRangeLoaded(range) => {
+                self.comparison_range = Some(range);
+            }
+            RangesMessage::CloseComparison => {
+                self.comparison_range = None;
+            }
+            RangesMessage::CycleCompareRange => {
+                let mut ids = self.select_range_buttons.iter().map(|b| &b.range.id);
+                self.compare_range = match &self.compare_range {
+                    None => ids.next(),
+                    Some(id) => ids.skip_while(|other| *other != id).nth(1),
+                }
+                .cloned();
+                self.heat_map = None;
+                self.comparison_range = None;
+            }
+            RangesMessage::RangeEquityComputed(range_id, range_equity) => {
+                if self.active_range.as_ref().map(|r| r.id) == Some(range_id) {
+                    self.heat_map = Some(range_equity);
+                }
+            }
+            RangesMessage::HandOrderingSelected(ordering, label) => {
+                self.hand_ordering = ordering;
+                self.hand_ordering_label = label;
+                self.hand_order = None;
+            }
+            RangesMessage::HandOrderComputed(label, order) => {
+                if self.hand_ordering_label == label {
+                    self.hand_order = Some(order);
+                }
+            }
+            RangesMessage::TopPercentChanged(percent) => {
+                self.top_percent = percent.round();
+                if let (Some(active_range), Some(order)) = (&mut self.active_range, &self.hand_order) {
+                    let hands = Range::from_order(self.top_percent as u8, order).hands;
+                    if hands != active_range.range.hands {
+                        active_range.record(Edit::TopPercent);
+                        active_range.range.hands = hands;
+                        self.heat_map = None;
+                        self.refresh_notation();
+                    }
+                }
+            }
+            RangesMessage::MetadataChanged(metadata) => {
+                if let Some(active_range) = &mut self.active_range {
+                    if active_range.range.metadata != metadata {
+                        active_range.record(Edit::Metadata);
+                        active_range.range.metadata = metadata;
+                    }
+                }
+            }
+            RangesMessage::FolderChanged(folder) => {
+                if let Some(active_range) = &mut self.active_range {
+                    if active_range.range.folder != folder {
+                        active_range.record(Edit::Folder);
+                        active_range.range.folder = folder;
+                    }
+                }
+            }
+            RangesMessage::TagsChanged(value) => {
+                if let Some(active_range) = &mut self.active_range {
+                    let mut range = active_range.range.clone();
+                    range.set_tags(&value);
+                    if range.tags != active_range.range.tags {
+                        active_range.record(Edit::Tags);
+                        active_range.range.tags = range.tags;
+                    }
+                }
+                self.tags_value = value;
+            }
+            RangesMessage::SearchChanged(value) => {
+                self.search_value = value;
+            }
+            RangesMessage::FilePathChanged(value) => {
+                self.file_path_value = value;
+            }
+            RangesMessage::CycleFileFormat => {
+                self.file_format = cycle(RangeFormat::iterator(), &Some(self.file_format))
+                    .unwrap_or_default();
+            }
+            RangesMessage::FileStatus(status) => {
+                self.file_status = status;
+            }
+            RangesMessage::Undo => {
+                if let Some(active_range) = &mut self.active_range {
+                    active_range.undo();
+                }
+                self.refresh_notation();
+                self.refresh_tags();
+            }
+            RangesMessage::Redo => {
+                if let Some(active_range) = &mut self.active_range {
+                    active_range.redo();
+                }
+                self.refresh_notation();
+                self.refresh_tags();
+            }
+            RangesMessage::InvertRange => {
+                if let Some(active_range) = &mut self.active_range {
+                    active_range.record(Edit::Hands);
+                    active_range.range = active_range.range.complement();
+                }
+                self.heat_map = None;
+                self.refresh_notation();
+            }
+            RangesMessage::RangesCombined(range_id, range) => {
+                if let Some(active_range) = &mut self.active_range {
+                    if active_range.id == range_id {
+                        active_range.record(Edit::Hands);
+                        active_range.range.hands = range.hands;
+                        self.heat_map = None;
+                        self.refresh_notation();
+                    }
+                }
+            }
+            RangesMessage::StrategyNameChanged(new_name) => {
+                if let Some(active_strategy) = &mut self.active_strategy {
+                    if active_strategy.strategy.name != new_name {
+                        active_strategy.strategy.name = new_name;
+                        active_strategy.dirty = true;
+                    }
+                }
+            }
+            RangesMessage::StrategyBrushSelected(brush) => {
+                self.strategy_brush = brush;
+            }
+            RangesMessage::RaiseSizeChanged(value) => {
+                self.raise_size_value = value;
+            }
+            RangesMessage::UpdateSelectStrategyButtons(strategies) => {
+                self.select_strategy_buttons = strategies
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| SelectStrategyButton::new(i, s.name.to_string()))
+                    .collect();
+            }
+            RangesMessage::StrategiesHaveBeenSaved => {
+                if let Some(active_strategy) = &mut self.active_strategy {
+                    active_strategy.dirty = false;
+                }
             }
             RangesMessage::RangeNameChanged(new_name) => {
                 if let Some(active_range) = &mut self.active_range {
-                    active_range.range.name = new_name;
+                    if active_range.range.name != new_name {
+                        active_range.record(Edit::Name);
+                        active_range.range.name = new_name;
+                    }
                 }
             }
             RangesMessage::RangesHaveBeenSaved => {
                 if let Some(active_range) = &mut self.active_range {
+                    active_range.saved = active_range.range.clone();
                     active_range.dirty = false;
                 }
             }
@@ -233,28 +1145,129 @@ impl RangesScreen {
     }
 }
 
+/// The option after `current`, going from unset through every option and
+/// back to unset.
+fn cycle<'a, T: 'a + Copy + PartialEq>(mut options: impl Iterator<Item = &'a T>, current: &Option<T>) -> Option<T> {
+    match current {
+        None => options.next().copied(),
+        Some(current) => options.skip_while(|o| *o != current).nth(1).copied(),
+    }
+}
+
+fn matrix_message(input: MatrixInput) -> Message {
+    Message::RangesScreen(RangesMessage::MatrixInput(input))
+}
+
+fn hand_matrix<'a, F>(drag: Option<&DragSession>, cell: F) -> Column<'a, Message>
+where
+    F: Fn(Hand) -> HandToggle<Message>,
+{
+    Card::iterator().rev().enumerate().fold(
+        Column::new().spacing(4),
+        |column, (row_idx, row_card)| {
+            column.push(Card::iterator().rev().enumerate().fold(
+                Row::new().height(Length::Fill).spacing(4),
+                |row, (col_idx, col_card)| {
+                    let suited = {
+                        if col_idx > row_idx {
+                            Suit::Suited
+                        } else {
+                            Suit::Off
+                        }
+                    };
+                    let first = Card::max(*row_card, *col_card);
+                    let second = Card::min(*row_card, *col_card);
+                    let hand = Hand {
+                        first,
+                        second,
+                        suited,
+                    };
+                    row.push(cell(hand).drag(
+                        drag.is_some(),
+                        matches!(drag, Some(d) if d.touched.contains(&hand)),
+                        matches!(drag, Some(d) if d.anchor == hand),
+                    ))
+                },
+            ))
+        },
+    )
+}
+
+const ONLY_FIRST_COLOR: Color = Color { r: 0.53, g: 0.82, b: 0.97, a: 1.0 };
+const BOTH_COLOR: Color = Color { r: 0.75, g: 0.6, b: 0.9, a: 1.0 };
+const ONLY_SECOND_COLOR: Color = Color { r: 0.97, g: 0.75, b: 0.47, a: 1.0 };
+
+fn action_color(action: &PokerAction) -> Color {
+    match action {
+        PokerAction::Raise(_) => Color::from_rgb8(240, 120, 110),
+        PokerAction::Call | PokerAction::Check => Color::from_rgb8(130, 210, 140),
+        PokerAction::Fold => Color::from_rgba8(136, 208, 247, 1.0),
+    }
+}
+
 struct HandToggle<Message> {
-    is_active: bool,
-    on_toggle: Box<dyn Fn(Hand) -> Message>,
+    weight: f32,
+    on_input: Box<dyn Fn(MatrixInput) -> Message>,
     hand: Hand,
+    heat: Option<f32>,
+    split: Vec<(Color, f32)>,
+    dragging: bool,
+    touched: bool,
+    anchor: bool,
 }
 
 impl<Message> HandToggle<Message> {
-    pub fn new<F>(is_active: bool, hand: Hand, on_toggle: F) -> Self
+    pub fn new<F>(weight: f32, hand: Hand, on_input: F) -> Self
     where
-        F: 'static + Fn(Hand) -> Message,
+        F: 'static + Fn(MatrixInput) -> Message,
     {
         Self {
-            is_active,
-            on_toggle: Box::new(on_toggle),
+            weight,
+            on_input: Box::new(on_input),
             hand,
+            heat: None,
+            split: vec![],
+            dragging: false,
+            touched: false,
+            anchor: false,
         }
     }
 
+    /// Tells the cell about a drag in progress. Cells only report the
+    /// cursor entering them once per drag, and only the cell the drag
+    /// started on reports the release.
+    pub fn drag(mut self, dragging: bool, touched: bool, anchor: bool) -> Self {
+        self.dragging = dragging;
+        self.touched = touched;
+        self.anchor = anchor;
+        self
+    }
+
+    /// Fills the cell from the left with one stripe per action, each as wide
+    /// as its frequency.
+    pub fn split(mut self, split: Vec<(Color, f32)>) -> Self {
+        self.split = split;
+        self
+    }
+
+    /// Shades the cell from red to green by an equity percentage.
+    pub fn heat(mut self, heat: Option<f32>) -> Self {
+        self.heat = heat;
+        self
+    }
+
     fn color(&self) -> Background {
-        match self.is_active {
-            true => Background::Color(Color::from_rgba8(136, 208, 247, 1.0)),
-            false => Background::Color(Color::WHITE),
+        match self.heat {
+            Some(heat) => {
+                let equity = (heat / 100.0).clamp(0.0, 1.0);
+                Background::Color(Color {
+                    r: if equity < 0.5 { 1.0 } else { 2.0 * (1.0 - equity) },
+                    g: if equity < 0.5 { 2.0 * equity } else { 1.0 },
+                    b: 0.3,
+                    a: 1.0,
+                })
+            }
+            None => Background::Color(Color::from_rgba8(136, 208, 247, 1.0)),
         }
     }
 }
@@ -282,11 +1295,24 @@ impl<Message> Widget<Message, Renderer> for HandToggle<Message> {
     ) -> (Primitive, MouseCursor) {
         let background = Primitive::Quad {
             bounds: layout.bounds(),
-            background: self.color(),
+            background: Background::Color(Color::WHITE),
             border_radius: 5,
             border_color: Color::BLACK,
             border_width: 1,
         };
+        // Mixed hands are only filled up to their weight, from the bottom.
+        let filled_height = layout.bounds().height * self.weight.clamp(0.0, 1.0);
+        let fill = Primitive::Quad {
+            bounds: Rectangle {
+                y: layout.bounds().y + layout.bounds().height - filled_height,
+                height: filled_height,
+                ..layout.bounds()
+            },
+            background: self.color(),
+            border_radius: 5,
+            border_color: Color::BLACK,
+            border_width: if self.weight > 0.0 { 1 } else { 0 },
+        };
         let hand_text = Primitive::Text {
             content: self.hand.to_string(),
             bounds: Rectangle {
@@ -303,17 +1329,60 @@ impl<Message> Widget<Message, Renderer> for HandToggle<Message> {
             horizontal_alignment: HorizontalAlignment::Center,
             vertical_alignment: VerticalAlignment::Center,
         };
+        let mut primitives = vec![background, fill];
+        let mut stripe_x = layout.bounds().x;
+        for (color, frequency) in self.split.iter() {
+            let width = layout.bounds().width * frequency;
+            primitives.push(Primitive::Quad {
+                bounds: Rectangle {
+                    x: stripe_x,
+                    width,
+                    ..layout.bounds()
+                },
+                background: Background::Color(*color),
+                border_radius: 0,
+                border_color: Color::TRANSPARENT,
+                border_width: 0,
+            });
+            stripe_x += width;
+        }
+        if !self.split.is_empty() {
+            primitives.push(Primitive::Quad {
+                bounds: layout.bounds(),
+                background: Background::Color(Color::TRANSPARENT),
+                border_radius: 5,
+                border_color: Color::BLACK,
+                border_width: 1,
+            });
+        }
+        primitives.push(hand_text);
+        if let Some(heat) = self.heat {
+            primitives.push(Primitive::Text {
+                content: format!("{:.0}%", heat),
+                bounds: Rectangle {
+                    x: layout.bounds().center_x(),
+                    y: layout.bounds().y + layout.bounds().height * 0.85,
+                    ..layout.bounds()
+                },
+                color: Color {
+                    a: defaults.text.color.a * 0.7,
+                    ..defaults.text.color
+                },
+                font: Font::Default,
+                size: 12.0,
+                horizontal_alignment: HorizontalAlignment::Center,
+                vertical_alignment: VerticalAlignment::Center,
+            });
+        }
         (
-            Primitive::Group {
-                primitives: vec![background, hand_text],
-            },
+            Primitive::Group { primitives },
             MouseCursor::Pointer,
         )
     }
 
     fn hash_layout(&self, state: &mut Hasher) {
         use std::hash::Hash;
-        self.is_active.hash(state)
+        self.weight.to_bits().hash(state)
     }
 
     fn on_event(
@@ -332,9 +1401,29 @@ impl<Message> Widget<Message, Renderer> for HandToggle<Message> {
             }) => {
                 let mouse_over = layout.bounds().contains(cursor_position);
                 if mouse_over {
-                    messages.push((self.on_toggle)(self.hand));
+                    messages.push((self.on_input)(MatrixInput::Press(self.hand)));
+                }
+            }
+            Event::Mouse(input::mouse::Event::Input {
+                button: input::mouse::Button::Right,
+                state: input::ButtonState::Pressed,
+            }) => {
+                let mouse_over = layout.bounds().contains(cursor_position);
+                if mouse_over {
+                    messages.push((self.on_input)(MatrixInput::ErasePress(self.hand)));
                 }
             }
+            Event::Mouse(input::mouse::Event::Input {
+                state: input::ButtonState::Released,
+                ..
+            }) if self.anchor => {
+                messages.push((self.on_input)(MatrixInput::Release));
+            }
+            Event::Mouse(input::mouse::Event::CursorMoved { x, y })
+                if self.dragging && !self.touched && layout.bounds().contains(Point::new(x, y)) =>
+            {
+                messages.push((self.on_input)(MatrixInput::DragOver(self.hand)));
+            }
             _ => {}
         }
     }
@@ -1,4 +1,5 @@
 use super::item;
+use super::scheduler::Grade;
 use crate::messages::Message;
 use crate::styles;
 use iced::{widget, Align, Button, Column, Container, Length, Row, Text, TextInput};
@@ -7,19 +8,19 @@ use iced_native::{button, text_input};
 #[derive(Debug, Clone)]
 pub enum ReviewDisplayMessage {
     AnswerChanged(String),
-    AnswerSubmitted(bool),
+    AnswerSubmitted(Grade),
 }
 
 enum ReviewDisplayState {
     Waiting,
-    Correct,
-    Wrong,
+    /// The answer is shown along with the grade suggested for it.
+    Revealed(Grade),
 }
 
 pub struct ReviewDisplay {
     review_item: item::ReviewItem,
     state: ReviewDisplayState,
-    next_button: button::State,
+    grade_buttons: [button::State; 4],
     yes_button: button::State,
     no_button: button::State,
     submit_button: button::State,
@@ -37,7 +38,7 @@ impl ReviewDisplay {
         Self {
             review_item,
             state: ReviewDisplayState::Waiting,
-            next_button: button::State::new(),
+            grade_buttons: Default::default(),
             yes_button: button::State::new(),
             no_button: button::State::new(),
             submit_button: button::State::new(),
@@ -86,10 +87,7 @@ impl ReviewDisplay {
                         .push(
                             Button::new(&mut self.yes_button, Text::new("Yes"))
                                 .on_press(Message::AnswerReviewItem(
-                                    (
-                                        self.review_item.question.presentation.clone(),
-                                        self.review_item.question.answer.clone(),
-                                    ),
+                                    self.review_item.question.id.clone(),
                                     item::Answer::Yes,
                                 ))
                                 .style(styles::Button::Basic),
@@ -97,10 +95,7 @@ impl ReviewDisplay {
                         .push(
                             Button::new(&mut self.no_button, Text::new("No"))
                                 .on_press(Message::AnswerReviewItem(
-                                    (
-                                        self.review_item.question.presentation.clone(),
-                                        self.review_item.question.answer.clone(),
-                                    ),
+                                    self.review_item.question.id.clone(),
                                     item::Answer::No,
                                 ))
                                 .style(styles::Button::Basic),
@@ -121,10 +116,7 @@ impl ReviewDisplay {
                         .push(
                             Button::new(&mut self.submit_button, Text::new("Submit"))
                                 .on_press(Message::AnswerReviewItem(
-                                    (
-                                        self.review_item.question.presentation.clone(),
-                                        self.review_item.question.answer.clone(),
-                                    ),
+                                    self.review_item.question.id.clone(),
                                     item::Answer::Text(item::DisplayString(
                                         self.answer_value.to_string(),
                                     )),
@@ -138,10 +130,7 @@ impl ReviewDisplay {
                         .push(
                             Button::new(&mut self.fold_button, Text::new("Fold"))
                                 .on_press(Message::AnswerReviewItem(
-                                    (
-                                        self.review_item.question.presentation.clone(),
-                                        self.review_item.question.answer.clone(),
-                                    ),
+                                    self.review_item.question.id.clone(),
                                     item::Answer::PokerAction(item::PokerAction::Fold),
                                 ))
                                 .style(styles::Button::Basic),
@@ -149,10 +138,7 @@ impl ReviewDisplay {
                         .push(
                             Button::new(&mut self.check_button, Text::new("Check"))
                                 .on_press(Message::AnswerReviewItem(
-                                    (
-                                        self.review_item.question.presentation.clone(),
-                                        self.review_item.question.answer.clone(),
-                                    ),
+                                    self.review_item.question.id.clone(),
                                     item::Answer::PokerAction(item::PokerAction::Check),
                                 ))
                                 .style(styles::Button::Basic),
@@ -160,10 +146,7 @@ impl ReviewDisplay {
                         .push(
                             Button::new(&mut self.call_button, Text::new("Call"))
                                 .on_press(Message::AnswerReviewItem(
-                                    (
-                                        self.review_item.question.presentation.clone(),
-                                        self.review_item.question.answer.clone(),
-                                    ),
+                                    self.review_item.question.id.clone(),
                                     item::Answer::PokerAction(item::PokerAction::Call),
                                 ))
                                 .style(styles::Button::Basic),
@@ -181,10 +164,7 @@ impl ReviewDisplay {
                         .push(
                             Button::new(&mut self.raise_bet_button, Text::new("Raise/Bet"))
                                 .on_press(Message::AnswerReviewItem(
-                                    (
-                                        self.review_item.question.presentation.clone(),
-                                        self.review_item.question.answer.clone(),
-                                    ),
+                                    self.review_item.question.id.clone(),
                                     item::Answer::PokerAction(item::PokerAction::Raise(
                                         self.answer_value.to_string(),
                                     )),
@@ -193,26 +173,25 @@ impl ReviewDisplay {
                         ),
                 ),
             },
-            ReviewDisplayState::Correct => col.push(
-                Button::new(
-                    &mut self.next_button,
-                    Text::new(format!(
-                        "Correct: {}",
-                        self.review_item.question.answer.reveal()
-                    )),
-                )
-                .on_press(Message::RequestNewQuestion),
-            ),
-            ReviewDisplayState::Wrong => col.push(
-                Button::new(
-                    &mut self.next_button,
-                    Text::new(format!(
-                        "Wrong: {}",
-                        self.review_item.question.answer.reveal()
-                    )),
-                )
-                .on_press(Message::RequestNewQuestion),
-            ),
+            ReviewDisplayState::Revealed(suggested) => {
+                let id = &self.review_item.question.id;
+                let grade_buttons = Grade::iterator().zip(self.grade_buttons.iter_mut()).fold(
+                    Row::new().spacing(8),
+                    |row, (grade, state)| {
+                        row.push(
+                            Button::new(state, Text::new(grade.to_string()))
+                                .on_press(Message::GradeReviewItem(id.clone(), *grade))
+                                .style(styles::Button::RangeList { selected: *grade == suggested }),
+                        )
+                    },
+                );
+                col.push(Text::new(format!(
+                    "{}: {}",
+                    if suggested.correct() { "Correct" } else { "Wrong" },
+                    self.review_item.question.answer.reveal()
+                )))
+                .push(grade_buttons)
+            }
         };
 
         let info_bar = Row::new()
@@ -265,12 +244,8 @@ impl ReviewDisplay {
             ReviewDisplayMessage::AnswerChanged(s) => {
                 self.answer_value = s;
             }
-            ReviewDisplayMessage::AnswerSubmitted(correct) => {
-                if correct {
-                    self.state = ReviewDisplayState::Correct;
-                } else {
-                    self.state = ReviewDisplayState::Wrong;
-                }
+            ReviewDisplayMessage::AnswerSubmitted(grade) => {
+                self.state = ReviewDisplayState::Revealed(grade);
             }
         }
     }
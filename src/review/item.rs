@@ -4,6 +4,7 @@ use regex::Regex;
 use lazy_static::lazy_static;
 
 use crate::hand::Hand;
+use super::scheduler::SchedulerState;
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -14,50 +15,48 @@ pub struct ReviewItem {
     pub days_between_review_attempts: f32,
     pub date_last_reviewed: Option<DateTime<Local>>,
     pub last_answer_correct: bool,
+    #[serde(default)]
+    pub scheduler_state: SchedulerState,
     pub question: Question,
 }
 
-impl ReviewItem {
-    pub fn percent_overdue(&self) -> f32 {
-        if self.last_answer_correct {
-            1.0
-        } else {
-            if let Some(date_last_reviewed) = self.date_last_reviewed {
-                f32::min(
-                    2.0,
-                    (Local::now() - date_last_reviewed).num_days() as f32
-                        / self.days_between_review_attempts,
-                )
-            } else {
-                1.0
-            }
-        }
-    }
-    
-    pub fn update(&mut self, correct: bool) {
-        self.date_last_reviewed = Some(Local::now());
-        self.last_answer_correct = correct;
-        let performace_rating = if self.last_answer_correct { 1.0 } else { 0.0 };
-        self.difficulty += self.percent_overdue() * (1.0/17.0) * (8.0 - 9.0 * performace_rating);
-        if self.difficulty > 1.0 {self.difficulty = 1.0};
-        if self.difficulty < 0.0 {self.difficulty = 0.0};
-        let difficulty_weight = 3.0 - 1.7 * self.difficulty;
-        if self.last_answer_correct {
-            self.days_between_review_attempts = 1.0 + (difficulty_weight - 1.0) * self.percent_overdue();
-        } else {
-            self.days_between_review_attempts = 1.0 / difficulty_weight.powf(2.0);
-        }
-    }
-}
-
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Question {
+    /// Empty for questions saved before they had IDs.
+    #[serde(default)]
+    pub id: QuestionId,
     pub presentation: Presentation,
     pub options: Options,
     pub answer: Answer,
     pub tolerance: Option<f32>,
 }
 
+/// Names a question independently of its wording and answer, e.g.
+/// `range/<range id>/contains/AKs`, so its history survives renaming or
+/// editing the range it is about.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash, Default)]
+pub struct QuestionId(pub String);
+
+impl QuestionId {
+    /// One of `choices`, picked by the ID so the same question always gets
+    /// the same choice while different questions spread over all of them.
+    pub fn pick<'a, T>(&self, choices: &'a [T]) -> &'a T {
+        // FNV-1a, which unlike the standard library's hasher won't change
+        // between releases
+        let hash = self
+            .0
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+        &choices[(hash % choices.len() as u64) as usize]
+    }
+}
+
+impl fmt::Display for QuestionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 // Display strings are not allowed to have the characters ⨼ or ⦙ 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
 pub struct DisplayString(pub String);
@@ -278,3 +277,21 @@ impl FromStr for PokerAction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::QuestionId;
+
+    #[test]
+    fn picks_are_stable_and_vary_by_question() {
+        let choices = [0, 1, 2, 3, 4, 5];
+        let picks = (0..24)
+            .map(|i| *QuestionId(format!("range/{}/blockers/AKs", i)).pick(&choices))
+            .collect::<Vec<_>>();
+        let again = (0..24)
+            .map(|i| *QuestionId(format!("range/{}/blockers/AKs", i)).pick(&choices))
+            .collect::<Vec<_>>();
+        assert_eq!(picks, again);
+        assert!(picks.iter().any(|pick| *pick != picks[0]));
+    }
+}
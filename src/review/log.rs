@@ -0,0 +1,84 @@
+use super::item::{Answer, QuestionId};
+use super::scheduler::{Grade, SchedulerKind};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// One answer to a review item. The review log keeps every one of them, a
+/// JSON object per line, oldest first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReviewLogEntry {
+    pub question_id: QuestionId,
+    pub timestamp: DateTime<Local>,
+    pub given_answer: Answer,
+    pub correct_answer: Answer,
+    pub grade: Grade,
+    /// From the question being shown to the answer being submitted.
+    pub response_time_ms: u64,
+    /// The scheduler that picked the item and how overdue it was then.
+    pub scheduler: SchedulerKind,
+    pub percent_overdue: f32,
+    /// Days until the item is due again after this answer.
+    pub days_between_review_attempts: f32,
+}
+
+/// The entry as a line of the log, newline included.
+pub fn to_line(entry: &ReviewLogEntry) -> Result<String, serde_json::Error> {
+    Ok(format!("{}\n", serde_json::to_string(entry)?))
+}
+
+/// The entry as a line to append to a log whose last byte is `log_end`. A
+/// log that doesn't end in a newline, such as one cut short by a crash
+/// mid-write, gets one first so the torn line doesn't swallow the entry.
+pub fn to_line_after(log_end: Option<u8>, entry: &ReviewLogEntry) -> Result<String, serde_json::Error> {
+    let line = to_line(entry)?;
+    match log_end {
+        Some(byte) if byte != b'\n' => Ok(format!("\n{}", line)),
+        _ => Ok(line),
+    }
+}
+
+/// Every entry in a log. Lines that cannot be read, such as one cut short
+/// by a crash mid-write, are skipped.
+pub fn parse(text: &str) -> Vec<ReviewLogEntry> {
+    text.lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+    use crate::review::item::{Answer, DisplayString, PokerAction, QuestionId};
+    use crate::review::scheduler::{Grade, SchedulerKind};
+    use super::ReviewLogEntry;
+
+    #[test]
+    fn logs_read_back_skipping_damaged_lines() {
+        let entry = ReviewLogEntry {
+            question_id: QuestionId(String::from("rfe/2/3")),
+            timestamp: Local.ymd(2020, 1, 1).and_hms(12, 0, 0),
+            given_answer: Answer::Text(DisplayString(String::from("40"))),
+            correct_answer: Answer::Text(DisplayString(String::from("40"))),
+            grade: Grade::Easy,
+            response_time_ms: 4_250,
+            scheduler: SchedulerKind::Fsrs,
+            percent_overdue: 1.2,
+            days_between_review_attempts: 3.7,
+        };
+        let other = ReviewLogEntry {
+            question_id: QuestionId(String::from("strategy/1/action/AKs")),
+            given_answer: Answer::PokerAction(PokerAction::Call),
+            correct_answer: Answer::PokerAction(PokerAction::Raise(String::from("2.5"))),
+            grade: Grade::Again,
+            ..entry.clone()
+        };
+        let first = super::to_line(&entry).unwrap();
+        let second = super::to_line(&other).unwrap();
+        assert!(first.ends_with('\n') && !first.trim_end().contains('\n'));
+        let mut log = format!("{}{}", first, &second[..second.len() / 2]);
+        log.push_str(&super::to_line_after(log.bytes().last(), &entry).unwrap());
+        assert_eq!(super::parse(&log), vec![entry.clone(), entry.clone()]);
+        assert_eq!(super::to_line_after(log.bytes().last(), &entry).unwrap(), first);
+        assert_eq!(super::to_line_after(None, &entry).unwrap(), first);
+    }
+}
@@ -3,3 +3,5 @@ pub use self::display::ReviewDisplay;
 
 pub mod item;
 pub mod display;
+pub mod scheduler;
+pub mod log;
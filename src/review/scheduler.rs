@@ -0,0 +1,523 @@
+use std::fmt;
+use std::slice::Iter;
+
+use super::item::ReviewItem;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Decides when review items come up again.
+pub trait Scheduler {
+    /// How overdue `item` is at `now`, 1.0 when it is exactly due. Items
+    /// are studied most overdue first.
+    fn percent_overdue(&self, item: &ReviewItem, now: DateTime<Local>) -> f32;
+
+    /// Records an answer to `item` given at `now`.
+    fn update(&self, item: &mut ReviewItem, grade: Grade, now: DateTime<Local>);
+
+    /// Replaces any per-item state this scheduler keeps with one derived
+    /// from `item`'s `difficulty` and `days_between_review_attempts`, which
+    /// every scheduler keeps current. Called when switching to this
+    /// scheduler, as state it saved before may be stale by then.
+    fn seed(&self, _item: &mut ReviewItem) {}
+}
+
+/// How well an answer was known.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Grade {
+    /// Wrong, or right by luck.
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl Grade {
+    pub fn iterator() -> Iter<'static, Grade> {
+        static GRADES: [Grade; 4] = [Grade::Again, Grade::Hard, Grade::Good, Grade::Easy];
+        GRADES.iter()
+    }
+
+    /// The grade an answer gets without rating it yourself.
+    pub fn from_correct(correct: bool) -> Self {
+        if correct { Grade::Good } else { Grade::Again }
+    }
+
+    /// Grades a numeric answer `error` away from the right one. Anything
+    /// outside `tolerance` is wrong, within half of it is Good and within a
+    /// tenth of it is Easy.
+    pub fn from_error(error: f32, tolerance: f32) -> Self {
+        let error = error.abs() / tolerance;
+        if error >= 1.0 {
+            Grade::Again
+        } else if error >= 0.5 {
+            Grade::Hard
+        } else if error >= 0.1 {
+            Grade::Good
+        } else {
+            Grade::Easy
+        }
+    }
+
+    pub fn correct(&self) -> bool {
+        *self != Grade::Again
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Grade::Again => "Again",
+            Grade::Hard => "Hard",
+            Grade::Good => "Good",
+            Grade::Easy => "Easy",
+        })
+    }
+}
+
+/// State kept for each review item by the schedulers that need more than
+/// `difficulty` and `days_between_review_attempts`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SchedulerState {
+    #[serde(default)]
+    pub sm2: Option<Sm2State>,
+    #[serde(default)]
+    pub fsrs: Option<FsrsState>,
+}
+
+/// The schedulers a profile can choose between.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum SchedulerKind {
+    #[default]
+    Legacy,
+    Sm2,
+    Fsrs,
+}
+
+impl SchedulerKind {
+    pub fn iterator() -> Iter<'static, SchedulerKind> {
+        static KINDS: [SchedulerKind; 3] = [SchedulerKind::Legacy, SchedulerKind::Sm2, SchedulerKind::Fsrs];
+        KINDS.iter()
+    }
+
+    pub fn scheduler(&self) -> Box<dyn Scheduler> {
+        match self {
+            SchedulerKind::Legacy => Box::new(Legacy),
+            SchedulerKind::Sm2 => Box::new(Sm2),
+            SchedulerKind::Fsrs => Box::new(Fsrs),
+        }
+    }
+}
+
+impl fmt::Display for SchedulerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            SchedulerKind::Legacy => "Classic",
+            SchedulerKind::Sm2 => "SM-2",
+            SchedulerKind::Fsrs => "FSRS",
+        })
+    }
+}
+
+fn days_since(item: &ReviewItem, now: DateTime<Local>) -> Option<f32> {
+    item.date_last_reviewed
+        .map(|date_last_reviewed| (now - date_last_reviewed).num_seconds() as f32 / 86_400.0)
+}
+
+/// Overdue by elapsed time over the current interval, capped at double.
+fn elapsed_over_interval(item: &ReviewItem, interval: f32, now: DateTime<Local>) -> f32 {
+    match days_since(item, now) {
+        Some(days) if interval > 0.0 => f32::min(2.0, days / interval),
+        _ => 1.0,
+    }
+}
+
+/// The formula the trainer has always used, driven only by `difficulty` and
+/// `days_between_review_attempts`. Good answers rate as it always rated a
+/// correct one, Hard ones as barely passing and Easy ones also stretch the
+/// interval by a bonus.
+pub struct Legacy;
+
+impl Legacy {
+    const EASY_BONUS: f32 = 1.3;
+}
+
+impl Scheduler for Legacy {
+    fn percent_overdue(&self, item: &ReviewItem, now: DateTime<Local>) -> f32 {
+        if item.last_answer_correct {
+            1.0
+        } else if let Some(date_last_reviewed) = item.date_last_reviewed {
+            f32::min(
+                2.0,
+                (now - date_last_reviewed).num_days() as f32 / item.days_between_review_attempts,
+            )
+        } else {
+            1.0
+        }
+    }
+
+    fn update(&self, item: &mut ReviewItem, grade: Grade, now: DateTime<Local>) {
+        item.date_last_reviewed = Some(now);
+        item.last_answer_correct = grade.correct();
+        let performace_rating = match grade {
+            Grade::Again => 0.0,
+            Grade::Hard => 0.6,
+            Grade::Good | Grade::Easy => 1.0,
+        };
+        item.difficulty += self.percent_overdue(item, now) * (1.0/17.0) * (8.0 - 9.0 * performace_rating);
+        if item.difficulty > 1.0 {item.difficulty = 1.0};
+        if item.difficulty < 0.0 {item.difficulty = 0.0};
+        let difficulty_weight = 3.0 - 1.7 * item.difficulty;
+        if item.last_answer_correct {
+            item.days_between_review_attempts = 1.0 + (difficulty_weight - 1.0) * self.percent_overdue(item, now);
+        } else {
+            item.days_between_review_attempts = 1.0 / difficulty_weight.powf(2.0);
+        }
+        if grade == Grade::Easy {
+            item.days_between_review_attempts *= Self::EASY_BONUS;
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Sm2State {
+    pub easiness: f32,
+    pub interval: f32,
+    pub repetitions: u32,
+}
+
+/// Piotr Wozniak's SuperMemo 2, with Again, Hard, Good and Easy as
+/// qualities 1, 3, 4 and 5.
+pub struct Sm2;
+
+impl Sm2 {
+    const MIN_EASINESS: f32 = 1.3;
+
+    fn state(item: &ReviewItem) -> Sm2State {
+        item.scheduler_state.sm2.unwrap_or_else(|| Self::derived(item))
+    }
+
+    fn derived(item: &ReviewItem) -> Sm2State {
+        Sm2State {
+            // difficulty 0.3, what new items start with, maps to the usual 2.5
+            easiness: f32::max(Self::MIN_EASINESS, 2.5 + (0.3 - item.difficulty) * 2.0),
+            interval: item.days_between_review_attempts,
+            repetitions: match (item.date_last_reviewed, item.last_answer_correct) {
+                (Some(_), true) => 2,
+                _ => 0,
+            },
+        }
+    }
+}
+
+impl Scheduler for Sm2 {
+    fn percent_overdue(&self, item: &ReviewItem, now: DateTime<Local>) -> f32 {
+        elapsed_over_interval(item, Self::state(item).interval, now)
+    }
+
+    fn update(&self, item: &mut ReviewItem, grade: Grade, now: DateTime<Local>) {
+        let mut state = Self::state(item);
+        let quality: f32 = match grade {
+            Grade::Again => 1.0,
+            Grade::Hard => 3.0,
+            Grade::Good => 4.0,
+            Grade::Easy => 5.0,
+        };
+        if grade.correct() {
+            state.interval = match state.repetitions {
+                0 => 1.0,
+                1 => 6.0,
+                _ => (state.interval * state.easiness).round(),
+            };
+            state.repetitions += 1;
+        } else {
+            state.interval = 1.0;
+            state.repetitions = 0;
+        }
+        state.easiness = f32::max(
+            Self::MIN_EASINESS,
+            state.easiness + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02),
+        );
+
+        item.date_last_reviewed = Some(now);
+        item.last_answer_correct = grade.correct();
+        item.days_between_review_attempts = state.interval;
+        item.difficulty = ((2.5 - state.easiness) / 2.0 + 0.3).clamp(0.0, 1.0);
+        item.scheduler_state.sm2 = Some(state);
+    }
+
+    fn seed(&self, item: &mut ReviewItem) {
+        item.scheduler_state.sm2 = Some(Self::derived(item));
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct FsrsState {
+    /// Days until recall probability falls to 90%.
+    pub stability: f32,
+    /// From 1 for the easiest items to 10 for the hardest.
+    pub difficulty: f32,
+}
+
+/// The FSRS memory model with its published default weights, aiming for
+/// 90% recall.
+pub struct Fsrs;
+
+impl Fsrs {
+    const WEIGHTS: [f32; 17] = [
+        0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367, 1.0461,
+        2.1072, 0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+    ];
+    const GOOD: f32 = 3.0;
+
+    fn rating(grade: Grade) -> f32 {
+        match grade {
+            Grade::Again => 1.0,
+            Grade::Hard => 2.0,
+            Grade::Good => 3.0,
+            Grade::Easy => 4.0,
+        }
+    }
+
+    /// State for an item answered before this scheduler was picked. Items
+    /// that have never been answered have none.
+    fn state(item: &ReviewItem) -> Option<FsrsState> {
+        item.scheduler_state.fsrs.or_else(|| Self::derived(item))
+    }
+
+    fn derived(item: &ReviewItem) -> Option<FsrsState> {
+        item.date_last_reviewed.map(|_| FsrsState {
+            stability: item.days_between_review_attempts.max(0.1),
+            difficulty: 1.0 + 9.0 * item.difficulty,
+        })
+    }
+
+    fn initial_difficulty(grade: f32) -> f32 {
+        let w = Self::WEIGHTS;
+        (w[4] - (grade - 3.0) * w[5]).clamp(1.0, 10.0)
+    }
+
+    fn retrievability(days: f32, stability: f32) -> f32 {
+        (1.0 + 19.0 / 81.0 * days / stability).powf(-0.5)
+    }
+}
+
+impl Scheduler for Fsrs {
+    fn percent_overdue(&self, item: &ReviewItem, now: DateTime<Local>) -> f32 {
+        match Self::state(item) {
+            Some(state) => elapsed_over_interval(item, state.stability, now),
+            None => 1.0,
+        }
+    }
+
+    fn update(&self, item: &mut ReviewItem, grade: Grade, now: DateTime<Local>) {
+        let w = Self::WEIGHTS;
+        let rating = Self::rating(grade);
+        let state = match Self::state(item) {
+            None => FsrsState {
+                stability: w[rating as usize - 1],
+                difficulty: Self::initial_difficulty(rating),
+            },
+            Some(state) => {
+                let days = days_since(item, now).unwrap_or(0.0).max(0.0);
+                let r = Self::retrievability(days, state.stability);
+                let difficulty = state.difficulty - w[6] * (rating - 3.0);
+                let difficulty = (w[7] * Self::initial_difficulty(Self::GOOD) + (1.0 - w[7]) * difficulty)
+                    .clamp(1.0, 10.0);
+                let stability = if grade.correct() {
+                    let bonus = match grade {
+                        Grade::Hard => w[15],
+                        Grade::Easy => w[16],
+                        _ => 1.0,
+                    };
+                    state.stability
+                        * (w[8].exp()
+                            * (11.0 - state.difficulty)
+                            * state.stability.powf(-w[9])
+                            * ((w[10] * (1.0 - r)).exp() - 1.0)
+                            * bonus
+                            + 1.0)
+                } else {
+                    (w[11]
+                        * state.difficulty.powf(-w[12])
+                        * ((state.stability + 1.0).powf(w[13]) - 1.0)
+                        * (w[14] * (1.0 - r)).exp())
+                    .min(state.stability)
+                };
+                FsrsState { stability, difficulty }
+            }
+        };
+
+        item.date_last_reviewed = Some(now);
+        item.last_answer_correct = grade.correct();
+        item.days_between_review_attempts = state.stability;
+        item.difficulty = (state.difficulty - 1.0) / 9.0;
+        item.scheduler_state.fsrs = Some(state);
+    }
+
+    fn seed(&self, item: &mut ReviewItem) {
+        item.scheduler_state.fsrs = Self::derived(item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+    use chrono::Duration;
+    use crate::review::item::{Answer, DisplayString, Options, Presentation, Question, QuestionId, ReviewItem};
+    use super::{Fsrs, Grade, Legacy, Scheduler, Sm2};
+    use super::Grade::{Again, Easy, Good, Hard};
+
+    fn new_item() -> ReviewItem {
+        ReviewItem {
+            difficulty: 0.3,
+            days_between_review_attempts: 3.0,
+            date_last_reviewed: None,
+            last_answer_correct: false,
+            scheduler_state: Default::default(),
+            question: Question {
+                id: QuestionId(String::from("test")),
+                presentation: Presentation::Text(DisplayString::new("?")),
+                options: Options::Binary,
+                answer: Answer::Yes,
+                tolerance: None,
+            },
+        }
+    }
+
+    /// Answers a new item on time, every time, and returns the interval
+    /// after each answer.
+    fn intervals(scheduler: &dyn Scheduler, grades: &[Grade]) -> Vec<f32> {
+        let mut item = new_item();
+        let mut now = Local.ymd(2020, 1, 1).and_hms(12, 0, 0);
+        let mut intervals = vec![];
+        for grade in grades {
+            scheduler.update(&mut item, *grade, now);
+            intervals.push(item.days_between_review_attempts);
+            now = now + Duration::seconds((item.days_between_review_attempts * 86_400.0) as i64);
+        }
+        intervals
+    }
+
+    /// The last interval of `grades` with the `Again` among them replaced by
+    /// each grade in turn.
+    fn intervals_by_grade(scheduler: &dyn Scheduler, grades: &[Grade]) -> Vec<f32> {
+        Grade::iterator()
+            .map(|grade| {
+                let grades = grades.iter().map(|g| if *g == Again { *grade } else { *g }).collect::<Vec<_>>();
+                *intervals(scheduler, &grades).last().unwrap()
+            })
+            .collect()
+    }
+
+    fn increasing(intervals: &[f32]) -> bool {
+        intervals.windows(2).all(|pair| pair[0] < pair[1])
+    }
+
+    #[test]
+    fn grades_numeric_answers_by_error() {
+        assert_eq!(Grade::from_error(5.0, 5.0), Again);
+        assert_eq!(Grade::from_error(-3.0, 5.0), Hard);
+        assert_eq!(Grade::from_error(1.0, 5.0), Good);
+        assert_eq!(Grade::from_error(0.0, 5.0), Easy);
+        assert_eq!(Grade::from_correct(true), Good);
+    }
+
+    #[test]
+    fn legacy_scheduling_is_unchanged() {
+        let mut item = new_item();
+        let now = Local::now();
+        Legacy.update(&mut item, Again, now);
+        assert!((item.difficulty - 0.3).abs() < 1e-6);
+        assert!((item.days_between_review_attempts - 1.0 / 2.49f32.powf(2.0)).abs() < 1e-4);
+        Legacy.update(&mut item, Good, now);
+        assert!((item.difficulty - 0.3 + 1.0 / 17.0).abs() < 1e-6);
+        assert_eq!(Legacy.percent_overdue(&item, now), 1.0);
+        assert!(increasing(&intervals_by_grade(&Legacy, &[Good, Again])));
+    }
+
+    #[test]
+    fn sm2_intervals() {
+        assert_eq!(intervals(&Sm2, &[Good, Good, Good, Good]), vec![1.0, 6.0, 15.0, 38.0]);
+        // easiness drops to 1.96 on the miss and the item starts over
+        assert_eq!(intervals(&Sm2, &[Good, Good, Again, Good, Good, Good]), vec![1.0, 6.0, 1.0, 1.0, 6.0, 12.0]);
+        // grades change easiness, which shows in the interval after next
+        assert_eq!(intervals_by_grade(&Sm2, &[Good, Good, Again, Good]), vec![1.0, 35.0, 38.0, 39.0]);
+    }
+
+    #[test]
+    fn fsrs_intervals() {
+        let good = intervals(&Fsrs, &[Good, Good, Good, Good]);
+        assert_eq!(good[0], 3.7145);
+        assert!(good.windows(2).all(|pair| pair[1] > pair[0] * 2.0));
+        let missed = intervals(&Fsrs, &[Good, Good, Again, Good]);
+        assert_eq!(missed[..2], good[..2]);
+        assert!(missed[2] < good[0]);
+        assert!(missed[3] < good[2]);
+        assert_eq!(intervals_by_grade(&Fsrs, &[Again]), vec![0.4872, 1.4003, 3.7145, 13.8206]);
+        assert!(increasing(&intervals_by_grade(&Fsrs, &[Good, Again])));
+    }
+
+    #[test]
+    fn items_are_due_when_their_interval_is_up() {
+        let schedulers: Vec<Box<dyn Scheduler>> = vec![Box::new(Sm2), Box::new(Fsrs)];
+        for scheduler in schedulers {
+            let mut item = new_item();
+            let now = Local.ymd(2020, 1, 1).and_hms(12, 0, 0);
+            scheduler.update(&mut item, Good, now);
+            let due = now + Duration::seconds((item.days_between_review_attempts * 86_400.0) as i64);
+            assert!((scheduler.percent_overdue(&item, due) - 1.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn seeds_state_from_legacy_fields() {
+        let mut item = new_item();
+        item.difficulty = 0.5;
+        item.days_between_review_attempts = 10.0;
+        item.date_last_reviewed = Some(Local::now());
+        item.last_answer_correct = true;
+        Sm2.seed(&mut item);
+        Fsrs.seed(&mut item);
+        let sm2 = item.scheduler_state.sm2.unwrap();
+        assert!((sm2.easiness - 2.1).abs() < 1e-6);
+        assert_eq!((sm2.interval, sm2.repetitions), (10.0, 2));
+        let fsrs = item.scheduler_state.fsrs.unwrap();
+        assert_eq!((fsrs.stability, fsrs.difficulty), (10.0, 5.5));
+
+        let mut item = new_item();
+        Fsrs.seed(&mut item);
+        assert_eq!(item.scheduler_state.fsrs, None);
+        Sm2.update(&mut item, Good, Local::now());
+        assert_eq!(item.days_between_review_attempts, 1.0);
+    }
+
+    #[test]
+    fn switching_schedulers_picks_up_the_latest_interval() {
+        let mut item = new_item();
+        let mut now = Local.ymd(2020, 1, 1).and_hms(12, 0, 0);
+        let mut answer = |scheduler: &dyn Scheduler, item: &mut ReviewItem, grade| {
+            scheduler.update(item, grade, now);
+            now = now + Duration::days(1);
+        };
+        for _ in 0..3 {
+            answer(&Sm2, &mut item, Good);
+        }
+        assert_eq!(item.days_between_review_attempts, 15.0);
+
+        Fsrs.seed(&mut item);
+        assert_eq!(item.scheduler_state.fsrs.unwrap().stability, 15.0);
+        answer(&Fsrs, &mut item, Again);
+        let missed = item.days_between_review_attempts;
+        assert!(missed < 15.0);
+
+        // back on SM-2 the miss counts, rather than the 15 days it left off at
+        Sm2.seed(&mut item);
+        let sm2 = item.scheduler_state.sm2.unwrap();
+        assert_eq!((sm2.interval, sm2.repetitions), (missed, 0));
+        answer(&Sm2, &mut item, Good);
+        assert_eq!(item.days_between_review_attempts, 1.0);
+
+        Fsrs.seed(&mut item);
+        assert_eq!(item.scheduler_state.fsrs.unwrap().stability, 1.0);
+    }
+}
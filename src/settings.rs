@@ -0,0 +1,12 @@
+use serde::{Serialize, Deserialize};
+
+use crate::review::scheduler::SchedulerKind;
+
+/// Preferences for the profile, kept in the app's data directory alongside
+/// its ranges and review items. Fields default so settings saved by older
+/// versions still load.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct Settings {
+    pub scheduler: SchedulerKind,
+}
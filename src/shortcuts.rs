@@ -0,0 +1,51 @@
+use iced::Subscription;
+use iced_native::futures::future;
+use iced_native::futures::stream::{BoxStream, StreamExt};
+use iced_native::input::{keyboard, ButtonState};
+use iced_native::subscription::{EventStream, Recipe};
+use iced_native::{Event, Hasher};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shortcut {
+    Undo,
+    Redo,
+}
+
+/// Listens for keyboard shortcuts. Every other event is dropped here so it
+/// does not reach `update`.
+pub fn shortcuts() -> Subscription<Shortcut> {
+    Subscription::from_recipe(Shortcuts)
+}
+
+struct Shortcuts;
+
+impl Recipe<Hasher, Event> for Shortcuts {
+    type Output = Shortcut;
+
+    fn hash(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+        std::any::TypeId::of::<Self>().hash(state);
+    }
+
+    fn stream(self: Box<Self>, input: EventStream) -> BoxStream<'static, Shortcut> {
+        input
+            .filter_map(|event| future::ready(shortcut(&event)))
+            .boxed()
+    }
+}
+
+fn shortcut(event: &Event) -> Option<Shortcut> {
+    match event {
+        Event::Keyboard(keyboard::Event::Input {
+            state: ButtonState::Pressed,
+            key_code,
+            modifiers,
+        }) if modifiers.control || modifiers.logo => match key_code {
+            keyboard::KeyCode::Z if modifiers.shift => Some(Shortcut::Redo),
+            keyboard::KeyCode::Z => Some(Shortcut::Undo),
+            keyboard::KeyCode::Y => Some(Shortcut::Redo),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use chrono::prelude::*;
+use chrono::Duration;
+
+use crate::review::item::{QuestionId, ReviewItem};
+use crate::review::log::ReviewLogEntry;
+
+/// Days of review history shown, today included.
+pub const HISTORY_DAYS: i64 = 14;
+/// Days ahead the forecast covers, today included.
+pub const FORECAST_DAYS: i64 = 14;
+
+/// The kinds of question the trainer asks, told apart by their IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum QuestionType {
+    RangeMembership,
+    Equity,
+    Blockers,
+    StrategyAction,
+    RequiredFoldEquity,
+    Other,
+}
+
+impl QuestionType {
+    pub fn of(id: &QuestionId) -> Self {
+        match id.0.split('/').collect::<Vec<&str>>().as_slice() {
+            ["range", _, "contains", _] => QuestionType::RangeMembership,
+            ["range", _, "equity", _] => QuestionType::Equity,
+            ["range", _, "blockers", _] => QuestionType::Blockers,
+            ["strategy", _, "action", _] => QuestionType::StrategyAction,
+            ["rfe", ..] => QuestionType::RequiredFoldEquity,
+            _ => QuestionType::Other,
+        }
+    }
+}
+
+impl fmt::Display for QuestionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            QuestionType::RangeMembership => "In range",
+            QuestionType::Equity => "Equity vs range",
+            QuestionType::Blockers => "Blockers",
+            QuestionType::StrategyAction => "Strategy actions",
+            QuestionType::RequiredFoldEquity => "Required fold equity",
+            QuestionType::Other => "Other",
+        })
+    }
+}
+
+/// The ID of the range or strategy a question is about, if it is about one.
+fn subject(id: &QuestionId) -> Option<&str> {
+    match id.0.split('/').collect::<Vec<&str>>().as_slice() {
+        ["range", subject, ..] | ["strategy", subject, ..] => Some(subject),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Tally {
+    pub reviews: usize,
+    pub correct: usize,
+}
+
+impl Tally {
+    fn add(&mut self, entry: &ReviewLogEntry) {
+        self.reviews += 1;
+        if entry.grade.correct() {
+            self.correct += 1;
+        }
+    }
+
+    /// Percent answered correctly, if there were any answers.
+    pub fn accuracy(&self) -> Option<f32> {
+        match self.reviews {
+            0 => None,
+            reviews => Some(self.correct as f32 / reviews as f32 * 100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub answers: Tally,
+    /// Only answers to items that had been answered before, so how much was
+    /// remembered rather than how much was new.
+    pub recall: Tally,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Statistics {
+    /// The last `HISTORY_DAYS` days, oldest first.
+    pub days: Vec<Day>,
+    pub by_question_type: Vec<(QuestionType, Tally)>,
+    /// By the name of the range or strategy questions were about.
+    pub by_range: Vec<(String, Tally)>,
+    pub total: Tally,
+    pub due_today: usize,
+    pub due_this_week: usize,
+    /// Items never answered, which are not due on any particular day.
+    pub new_items: usize,
+    /// Items falling due on each of the next `FORECAST_DAYS` days, those
+    /// already overdue counted today.
+    pub forecast: Vec<(NaiveDate, usize)>,
+}
+
+impl Statistics {
+    /// Works out statistics at `now` from the review log and review items.
+    /// `names` gives the names of ranges and strategies by ID.
+    pub fn compute<'a, I>(log: &[ReviewLogEntry], review_items: I, names: &HashMap<String, String>, now: DateTime<Local>) -> Self
+    where
+        I: IntoIterator<Item = &'a ReviewItem>,
+    {
+        let today = now.date().naive_local();
+        let mut statistics = Statistics {
+            days: (0..HISTORY_DAYS)
+                .rev()
+                .map(|days_ago| Day {
+                    date: today - Duration::days(days_ago),
+                    answers: Tally::default(),
+                    recall: Tally::default(),
+                })
+                .collect(),
+            forecast: (0..FORECAST_DAYS).map(|days| (today + Duration::days(days), 0)).collect(),
+            ..Statistics::default()
+        };
+
+        let mut by_question_type = BTreeMap::<QuestionType, Tally>::new();
+        let mut by_range = BTreeMap::<String, Tally>::new();
+        let mut seen = HashSet::<&QuestionId>::new();
+        for entry in log {
+            statistics.total.add(entry);
+            by_question_type.entry(QuestionType::of(&entry.question_id)).or_default().add(entry);
+            if let Some(subject) = subject(&entry.question_id) {
+                let name = names.get(subject).cloned().unwrap_or_else(|| String::from("(deleted)"));
+                by_range.entry(name).or_default().add(entry);
+            }
+            let answered_before = !seen.insert(&entry.question_id);
+            let date = entry.timestamp.date().naive_local();
+            if let Some(day) = statistics.days.iter_mut().find(|day| day.date == date) {
+                day.answers.add(entry);
+                if answered_before {
+                    day.recall.add(entry);
+                }
+            }
+        }
+        statistics.by_question_type = by_question_type.into_iter().collect();
+        statistics.by_range = by_range.into_iter().collect();
+
+        for review_item in review_items {
+            match due_date(review_item) {
+                Some(due) => {
+                    let days = (due.date().naive_local() - today).num_days().max(0);
+                    if let Some((_, count)) = statistics.forecast.get_mut(days as usize) {
+                        *count += 1;
+                    }
+                }
+                None => statistics.new_items += 1,
+            }
+        }
+        statistics.due_today = statistics.forecast[0].1;
+        statistics.due_this_week = statistics.forecast.iter().take(7).map(|(_, count)| count).sum();
+        statistics
+    }
+}
+
+/// When an answered item is next due.
+fn due_date(review_item: &ReviewItem) -> Option<DateTime<Local>> {
+    review_item.date_last_reviewed.map(|date_last_reviewed| {
+        date_last_reviewed + Duration::seconds((review_item.days_between_review_attempts * 86_400.0) as i64)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use chrono::prelude::*;
+    use chrono::Duration;
+    use crate::review::item::{Answer, DisplayString, Options, Presentation, Question, QuestionId, ReviewItem};
+    use crate::review::log::ReviewLogEntry;
+    use crate::review::scheduler::{Grade, SchedulerKind};
+    use super::{QuestionType, Statistics, Tally};
+
+    fn entry(id: &str, timestamp: DateTime<Local>, grade: Grade) -> ReviewLogEntry {
+        ReviewLogEntry {
+            question_id: QuestionId(String::from(id)),
+            timestamp,
+            given_answer: Answer::Yes,
+            correct_answer: Answer::Yes,
+            grade,
+            response_time_ms: 1_000,
+            scheduler: SchedulerKind::Legacy,
+            percent_overdue: 1.0,
+            days_between_review_attempts: 1.0,
+        }
+    }
+
+    fn item(date_last_reviewed: Option<DateTime<Local>>, days_between_review_attempts: f32) -> ReviewItem {
+        ReviewItem {
+            difficulty: 0.3,
+            days_between_review_attempts,
+            date_last_reviewed,
+            last_answer_correct: true,
+            scheduler_state: Default::default(),
+            question: Question {
+                id: QuestionId(String::from("rfe/2/3")),
+                presentation: Presentation::Text(DisplayString::new("?")),
+                options: Options::Numbers,
+                answer: Answer::Text(DisplayString::new("40")),
+                tolerance: Some(9.6),
+            },
+        }
+    }
+
+    #[test]
+    fn tells_question_types_apart() {
+        let of = |id: &str| QuestionType::of(&QuestionId(String::from(id)));
+        assert_eq!(of("range/00ff/contains/AKs"), QuestionType::RangeMembership);
+        assert_eq!(of("range/00ff/equity/AKs"), QuestionType::Equity);
+        assert_eq!(of("range/00ff/blockers/AKs"), QuestionType::Blockers);
+        assert_eq!(of("strategy/00ff/action/AKs"), QuestionType::StrategyAction);
+        assert_eq!(of("rfe/2/3"), QuestionType::RequiredFoldEquity);
+        assert_eq!(of(""), QuestionType::Other);
+    }
+
+    #[test]
+    fn tallies_answers_by_day_type_and_range() {
+        let now = Local.ymd(2020, 3, 10).and_hms(20, 0, 0);
+        let yesterday = now - Duration::days(1);
+        let log = vec![
+            entry("range/a/contains/AKs", yesterday, Grade::Again),
+            entry("range/a/contains/AKs", now, Grade::Good),
+            entry("range/b/equity/AKs", now, Grade::Hard),
+            entry("strategy/c/action/AKs", now, Grade::Again),
+            entry("rfe/2/3", now - Duration::days(30), Grade::Easy),
+        ];
+        let names = vec![(String::from("a"), String::from("UTG open")), (String::from("c"), String::from("BTN"))]
+            .into_iter()
+            .collect::<HashMap<String, String>>();
+        let statistics = Statistics::compute(&log, vec![], &names, now);
+
+        assert_eq!(statistics.days.len(), 14);
+        let today = statistics.days.last().unwrap();
+        assert_eq!(today.date, NaiveDate::from_ymd(2020, 3, 10));
+        assert_eq!(today.answers, Tally { reviews: 3, correct: 2 });
+        assert_eq!(today.recall, Tally { reviews: 1, correct: 1 });
+        assert_eq!(statistics.days[12].answers, Tally { reviews: 1, correct: 0 });
+        assert_eq!(statistics.total, Tally { reviews: 5, correct: 3 });
+
+        assert_eq!(statistics.by_question_type, vec![
+            (QuestionType::RangeMembership, Tally { reviews: 2, correct: 1 }),
+            (QuestionType::Equity, Tally { reviews: 1, correct: 1 }),
+            (QuestionType::StrategyAction, Tally { reviews: 1, correct: 0 }),
+            (QuestionType::RequiredFoldEquity, Tally { reviews: 1, correct: 1 }),
+        ]);
+        assert_eq!(statistics.by_range, vec![
+            (String::from("(deleted)"), Tally { reviews: 1, correct: 1 }),
+            (String::from("BTN"), Tally { reviews: 1, correct: 0 }),
+            (String::from("UTG open"), Tally { reviews: 2, correct: 1 }),
+        ]);
+        assert_eq!(statistics.by_range[2].1.accuracy(), Some(50.0));
+        assert_eq!(Tally::default().accuracy(), None);
+    }
+
+    #[test]
+    fn forecasts_when_items_fall_due() {
+        let now = Local.ymd(2020, 3, 10).and_hms(12, 0, 0);
+        let items = vec![
+            item(None, 3.0),
+            item(Some(now - Duration::days(10)), 2.0),
+            item(Some(now), 0.1),
+            item(Some(now), 1.0),
+            item(Some(now), 6.0),
+            item(Some(now), 30.0),
+        ];
+        let statistics = Statistics::compute(&[], &items, &HashMap::new(), now);
+        assert_eq!(statistics.new_items, 1);
+        assert_eq!(statistics.due_today, 2);
+        assert_eq!(statistics.due_this_week, 4);
+        assert_eq!(
+            statistics.forecast.iter().map(|(_, count)| *count).collect::<Vec<usize>>(),
+            vec![2, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(statistics.forecast[6].0, NaiveDate::from_ymd(2020, 3, 16));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::messages::Message;
+use crate::statistics::{Statistics, Tally, FORECAST_DAYS, HISTORY_DAYS};
+use crate::styles;
+
+use iced::{scrollable, Column, Container, Row, Scrollable, Space, Text};
+use iced_native::{Align, Length};
+
+const LABEL_WIDTH: u16 = 160;
+const BAR_WIDTH: u16 = 240;
+const BAR_HEIGHT: u16 = 12;
+
+#[derive(Default)]
+pub struct StatisticsScreen {
+    statistics: Statistics,
+    scrollable: scrollable::State,
+}
+
+impl StatisticsScreen {
+    pub fn set_statistics(&mut self, statistics: Statistics) {
+        self.statistics = statistics;
+    }
+
+    pub fn view(&mut self) -> Container<'_, Message> {
+        let statistics = &self.statistics;
+
+        let summary = Row::new()
+            .spacing(24)
+            .push(Text::new(format!("Due today: {}", statistics.due_today)))
+            .push(Text::new(format!("Due this week: {}", statistics.due_this_week)))
+            .push(Text::new(format!("New: {}", statistics.new_items)))
+            .push(Text::new(format!(
+                "Answered: {} ({} correct)",
+                statistics.total.reviews,
+                accuracy(&statistics.total)
+            )));
+
+        let most_reviews = statistics.days.iter().map(|day| day.answers.reviews).max().unwrap_or(0);
+        let days = statistics.days.iter().fold(
+            section(&format!("Reviews over the last {} days", HISTORY_DAYS)),
+            |column, day| {
+                column.push(bar_row(
+                    &day.date.format("%a %e %b").to_string(),
+                    day.answers.reviews,
+                    most_reviews,
+                    &format!(
+                        "{} answered, {} correct, {} of repeats recalled",
+                        day.answers.reviews,
+                        accuracy(&day.answers),
+                        accuracy(&day.recall)
+                    ),
+                ))
+            },
+        );
+
+        let by_question_type = statistics.by_question_type.iter().fold(
+            section("Accuracy by question type"),
+            |column, (question_type, tally)| column.push(accuracy_row(&question_type.to_string(), tally)),
+        );
+
+        let by_range = statistics.by_range.iter().fold(
+            section("Accuracy by range and strategy"),
+            |column, (name, tally)| column.push(accuracy_row(name, tally)),
+        );
+
+        let most_due = statistics.forecast.iter().map(|(_, count)| *count).max().unwrap_or(0);
+        let forecast = statistics.forecast.iter().fold(
+            section(&format!("Items due over the next {} days", FORECAST_DAYS)),
+            |column, (date, count)| {
+                column.push(bar_row(
+                    &date.format("%a %e %b").to_string(),
+                    *count,
+                    most_due,
+                    &count.to_string(),
+                ))
+            },
+        );
+
+        Container::new(
+            Scrollable::new(&mut self.scrollable)
+                .spacing(24)
+                .padding(24)
+                .push(summary)
+                .push(days)
+                .push(by_question_type)
+                .push(by_range)
+                .push(forecast),
+        )
+        .width(Length::Fill)
+        .center_x()
+    }
+}
+
+fn section<'a>(title: &str) -> Column<'a, Message> {
+    Column::new().spacing(4).push(Text::new(title).size(24))
+}
+
+/// `value` as a bar scaled against `max`, between a label and a note.
+fn bar_row<'a>(label: &str, value: usize, max: usize, note: &str) -> Row<'a, Message> {
+    let width = (BAR_WIDTH as usize * value).checked_div(max).unwrap_or(0) as u16;
+    Row::new()
+        .spacing(8)
+        .align_items(Align::Center)
+        .push(Text::new(label).width(Length::Units(LABEL_WIDTH)))
+        .push(
+            Row::new()
+                .width(Length::Units(BAR_WIDTH))
+                .push(
+                    Container::new(Space::new(Length::Units(width), Length::Units(BAR_HEIGHT)))
+                        .style(styles::Container::Bar),
+                ),
+        )
+        .push(Text::new(note).color([0.5, 0.5, 0.5]))
+}
+
+fn accuracy_row<'a>(label: &str, tally: &Tally) -> Row<'a, Message> {
+    let correct = tally.accuracy().unwrap_or(0.0).round() as usize;
+    bar_row(label, correct, 100, &format!("{} correct of {}", accuracy(tally), tally.reviews))
+}
+
+fn accuracy(tally: &Tally) -> String {
+    match tally.accuracy() {
+        Some(accuracy) => format!("{:.0}%", accuracy),
+        None => String::from("-"),
+    }
+}
@@ -0,0 +1,184 @@
+use serde::{Serialize, Deserialize};
+use crate::hand::Hand;
+use crate::range::{self, Range};
+use crate::review::item::PokerAction;
+
+/// A chart splitting every hand between actions, e.g. raise 70% and call
+/// 30%. Each action keeps a weighted range of how often it is taken and
+/// whatever frequency is left over folds.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Strategy {
+    /// Empty for strategies saved before IDs existed.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub actions: Vec<(PokerAction, Range)>,
+}
+
+impl Strategy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn range(&self, action: &PokerAction) -> Option<&Range> {
+        self.actions.iter().find(|(a, _)| a == action).map(|(_, range)| range)
+    }
+
+    /// How often `hand` takes `action`. Folding is whatever the other actions
+    /// leave over.
+    pub fn frequency(&self, hand: &Hand, action: &PokerAction) -> f32 {
+        match action {
+            PokerAction::Fold => (1.0 - self.played(hand, None)).max(0.0),
+            _ => self.range(action).map(|range| range.weight(hand)).unwrap_or(0.0),
+        }
+    }
+
+    /// Every action `hand` takes with its frequency, folding last.
+    pub fn distribution(&self, hand: &Hand) -> Vec<(PokerAction, f32)> {
+        let mut distribution = self
+            .actions
+            .iter()
+            .map(|(action, range)| (action.clone(), range.weight(hand)))
+            .filter(|(_, frequency)| *frequency > 0.0)
+            .collect::<Vec<(PokerAction, f32)>>();
+        let fold = self.frequency(hand, &PokerAction::Fold);
+        if fold > 0.0 {
+            distribution.push((PokerAction::Fold, fold));
+        }
+        distribution
+    }
+
+    /// The action `hand` takes most often, preferring earlier actions on ties.
+    pub fn preferred_action(&self, hand: &Hand) -> PokerAction {
+        let mut preferred = (PokerAction::Fold, 0.0);
+        for (action, frequency) in self.distribution(hand) {
+            if frequency > preferred.1 {
+                preferred = (action, frequency);
+            }
+        }
+        preferred.0
+    }
+
+    /// Plays `hand` as `action` with `frequency`, scaling the other actions
+    /// down if they no longer fit.
+    pub fn set_frequency(&mut self, hand: Hand, action: PokerAction, frequency: f32) {
+        let frequency = frequency.clamp(0.0, 1.0);
+        if action != PokerAction::Fold {
+            match self.actions.iter_mut().find(|(a, _)| *a == action) {
+                Some((_, range)) => range.set_weight(hand, frequency),
+                None => {
+                    let mut range = Range::new();
+                    range.name = action_name(&action);
+                    range.set_weight(hand, frequency);
+                    self.actions.push((action.clone(), range));
+                }
+            }
+        }
+
+        let others = self.played(&hand, Some(&action));
+        if others > 1.0 - frequency {
+            let scale = (1.0 - frequency) / others;
+            for (_, range) in self.actions.iter_mut().filter(|(a, _)| *a != action) {
+                let weight = range.weight(&hand);
+                range.set_weight(hand, weight * scale);
+            }
+        }
+        self.actions.retain(|(_, range)| !range.hands.is_empty());
+    }
+
+    /// Sets `hand` to take `action` with `frequency`, or stops taking it if
+    /// it already does exactly that.
+    pub fn paint(&mut self, hand: Hand, action: PokerAction, frequency: f32) {
+        if action != PokerAction::Fold && self.frequency(&hand, &action) == frequency {
+            self.set_frequency(hand, action, 0.0);
+        } else {
+            self.set_frequency(hand, action, frequency);
+        }
+    }
+
+    /// Total frequency of the non fold actions other than `except`.
+    fn played(&self, hand: &Hand, except: Option<&PokerAction>) -> f32 {
+        self.actions
+            .iter()
+            .filter(|(action, _)| Some(action) != except)
+            .map(|(_, range)| range.weight(hand))
+            .sum()
+    }
+}
+
+impl Default for Strategy {
+    fn default() -> Strategy {
+        Strategy {
+            id: range::new_id(),
+            name: String::from("untitled strategy"),
+            actions: vec![],
+        }
+    }
+}
+
+pub fn action_name(action: &PokerAction) -> String {
+    match action {
+        PokerAction::Fold => String::from("Fold"),
+        PokerAction::Check => String::from("Check"),
+        PokerAction::Call => String::from("Call"),
+        PokerAction::Raise(size) => format!("Raise {} BB", size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hand::Hand;
+    use crate::review::item::PokerAction;
+    use super::Strategy;
+
+    fn raise() -> PokerAction {
+        PokerAction::Raise(String::from("2.5"))
+    }
+
+    #[test]
+    fn fold_is_the_remainder() {
+        let hand = "A5s".parse::<Hand>().unwrap();
+        let mut strategy = Strategy::new();
+        assert_eq!(strategy.frequency(&hand, &PokerAction::Fold), 1.0);
+        strategy.set_frequency(hand, raise(), 0.5);
+        strategy.set_frequency(hand, PokerAction::Call, 0.25);
+        assert_eq!(strategy.frequency(&hand, &PokerAction::Fold), 0.25);
+        assert_eq!(
+            strategy.distribution(&hand),
+            vec![(raise(), 0.5), (PokerAction::Call, 0.25), (PokerAction::Fold, 0.25)]
+        );
+        assert_eq!(strategy.preferred_action(&hand), raise());
+    }
+
+    #[test]
+    fn new_actions_squeeze_the_others() {
+        let hand = "KQo".parse::<Hand>().unwrap();
+        let mut strategy = Strategy::new();
+        strategy.set_frequency(hand, PokerAction::Call, 1.0);
+        strategy.set_frequency(hand, raise(), 0.75);
+        assert_eq!(strategy.frequency(&hand, &PokerAction::Call), 0.25);
+        strategy.set_frequency(hand, PokerAction::Fold, 1.0);
+        assert_eq!(strategy.distribution(&hand), vec![(PokerAction::Fold, 1.0)]);
+        assert!(strategy.actions.is_empty());
+    }
+
+    #[test]
+    fn painting_twice_clears() {
+        let hand = "TT".parse::<Hand>().unwrap();
+        let mut strategy = Strategy::new();
+        strategy.paint(hand, raise(), 1.0);
+        assert_eq!(strategy.preferred_action(&hand), raise());
+        strategy.paint(hand, raise(), 1.0);
+        assert_eq!(strategy.preferred_action(&hand), PokerAction::Fold);
+    }
+
+    #[test]
+    fn strategies_round_trip() {
+        let mut strategy = Strategy::new();
+        strategy.set_frequency("AKo".parse::<Hand>().unwrap(), raise(), 0.5);
+        let json = serde_json::to_string(&strategy).unwrap();
+        let loaded: Strategy = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.actions.len(), 1);
+        assert_eq!(loaded.range(&raise()).unwrap().hands, strategy.range(&raise()).unwrap().hands);
+    }
+}
@@ -1,23 +1,45 @@
 use crate::messages::Message;
 
 use crate::review;
+use crate::review::scheduler::SchedulerKind;
+use crate::styles;
 
-use iced::{Container, Text};
+use iced::{button, Align, Button, Column, Container, Text};
 
 pub struct StudyScreen {
     review_display: Option<review::display::ReviewDisplay>,
+    scheduler_button: button::State,
 }
 
 impl StudyScreen {
     pub fn new(review_display: Option<review::display::ReviewDisplay>) -> Self {
-        Self { review_display }
+        Self {
+            review_display,
+            scheduler_button: button::State::new(),
+        }
     }
 
-    pub fn view(&mut self) -> Container<Message> {
-        match &mut self.review_display {
+    pub fn view(&mut self, scheduler: SchedulerKind) -> Container<'_, Message> {
+        let next_scheduler = SchedulerKind::iterator()
+            .skip_while(|kind| **kind != scheduler)
+            .nth(1)
+            .copied()
+            .unwrap_or_default();
+        let question = match &mut self.review_display {
             Some(review_display) => review_display.view(),
             None => Container::new(Text::new("No available questions.")),
-        }
+        };
+        Container::new(
+            Column::new()
+                .spacing(16)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.scheduler_button, Text::new(format!("Scheduler: {}", scheduler)))
+                        .on_press(Message::SelectScheduler(next_scheduler))
+                        .style(styles::Button::Basic),
+                )
+                .push(question),
+        )
     }
 
     pub fn update(&mut self, message: review::display::ReviewDisplayMessage) {
@@ -2,6 +2,7 @@ use iced::{button, Color, Background, container};
 
 const DARK_GREY: Color = Color{r: 0.25, g: 0.25, b: 0.25, a: 1.0};
 const LIGHT_GREY: Color = Color{r: 0.75, g: 0.75, b: 0.75, a: 1.0};
+const BAR_BLUE: Color = Color{r: 0.53, g: 0.82, b: 0.97, a: 1.0};
 
 pub enum Button {
     Toolbar,
@@ -47,6 +48,7 @@ impl button::StyleSheet for Button {
 
 pub enum Container {
     Basic,
+    Bar,
 }
 
 impl container::StyleSheet for Container {
@@ -58,7 +60,12 @@ impl container::StyleSheet for Container {
                 border_width: 1,
                 background: None,
                 ..container::Style::default()
-            }
+            },
+            Container::Bar => container::Style {
+                border_radius: 2,
+                background: Some(Background::Color(BAR_BLUE)),
+                ..container::Style::default()
+            },
         }
     }
 }
@@ -9,6 +9,7 @@ use crate::styles;
 pub struct ToolBar {
     study_button: button::State,
     ranges_button: button::State,
+    statistics_button: button::State,
 }
 
 impl ToolBar {
@@ -27,6 +28,11 @@ impl ToolBar {
                     Button::new(&mut self.ranges_button, Text::new("Ranges"))
                         .on_press(Message::ViewRangesScreen)
                         .style(styles::Button::Toolbar),
+                )
+                .push(
+                    Button::new(&mut self.statistics_button, Text::new("Statistics"))
+                        .on_press(Message::ViewStatisticsScreen)
+                        .style(styles::Button::Toolbar),
                 ),
         )
         .style(styles::Container::Basic)